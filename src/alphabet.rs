@@ -0,0 +1,183 @@
+use crate::{Tile, WordError, WordN};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The tiles that words in a language are spelled with, like the Spanish `ñ` or the Welsh
+/// `ll`, which is one tile rather than two `l`s.
+///
+/// Words are read by matching the longest tile at each point, so Welsh `llan` is `ll`, `a`,
+/// `n`.  Accented letters have to be precomposed, as they usually are when typed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alphabet {
+    tiles: Vec<Tile>,
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::english()
+    }
+}
+
+fn tiles(letters: &str) -> Vec<Tile> {
+    letters
+        .split_whitespace()
+        .filter_map(|letters| Tile::new(letters).ok())
+        .collect()
+}
+
+const ENGLISH: &str = "a b c d e f g h i j k l m n o p q r s t u v w x y z";
+
+impl Alphabet {
+    /// Makes an alphabet from its tiles, in either case.
+    pub fn new<'a, I: IntoIterator<Item = &'a str>>(letters: I) -> Result<Alphabet, WordError> {
+        let mut tiles = vec![];
+        for letters in letters {
+            let tile = Tile::new(letters)?;
+            if !tiles.contains(&tile) {
+                tiles.push(tile);
+            }
+        }
+        Ok(Alphabet { tiles })
+    }
+
+    /// `a` to `z`.
+    pub fn english() -> Alphabet {
+        Alphabet {
+            tiles: tiles(ENGLISH),
+        }
+    }
+
+    /// `a` to `z`, `ñ`, and the accented vowels.  Those are tiles of their own, so `ó` in a
+    /// guess doesn't score against `o` in the target.
+    pub fn spanish() -> Alphabet {
+        Alphabet {
+            tiles: tiles(&format!("{} ñ á é í ó ú ü", ENGLISH)),
+        }
+    }
+
+    /// `a` to `z`, the umlauts and `ß`.
+    pub fn german() -> Alphabet {
+        Alphabet {
+            tiles: tiles(&format!("{} ä ö ü ß", ENGLISH)),
+        }
+    }
+
+    /// The Welsh alphabet, with its digraphs as single tiles.
+    pub fn welsh() -> Alphabet {
+        Alphabet {
+            tiles: tiles("a b c ch d dd e f ff g ng h i j l ll m n o p ph r rh s t th u w y"),
+        }
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    pub fn contains(&self, tile: Tile) -> bool {
+        self.tiles.contains(&tile)
+    }
+
+    /// Reads `value` as a word of `N` tiles, in any mix of case, without checking the word
+    /// lists.
+    pub fn word<const N: usize>(&self, value: &str) -> Result<WordN<N>, WordError> {
+        let lower = value.to_lowercase();
+        let mut rest = lower.as_str();
+        let mut word = vec![];
+        while let Some(x) = rest.chars().next() {
+            let tile = self
+                .tiles
+                .iter()
+                .filter(|tile| rest.starts_with(tile.as_str()))
+                .max_by_key(|tile| tile.as_str().len())
+                .ok_or_else(|| WordError::Chars(value.into(), x))?;
+            rest = &rest[tile.as_str().len()..];
+            word.push(*tile);
+        }
+        let tiles: [Tile; N] = word
+            .try_into()
+            .map_err(|word: Vec<Tile>| WordError::Length(word.len(), N))?;
+        Ok(WordN(tiles))
+    }
+}
+
+impl Display for Alphabet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, tile) in self.tiles.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", tile)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `english`, `spanish`, `german` or `welsh`, or a list of tiles separated by commas,
+/// like `a,b,c,ch`.
+impl FromStr for Alphabet {
+    type Err = WordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "english" => Ok(Alphabet::english()),
+            "spanish" => Ok(Alphabet::spanish()),
+            "german" => Ok(Alphabet::german()),
+            "welsh" => Ok(Alphabet::welsh()),
+            _ => Alphabet::new(s.split(',').map(str::trim)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::alphabet::Alphabet;
+    use crate::{Word, WordError, WordGuessN, WordN};
+    use anyhow::Error;
+
+    #[test]
+    fn english_agrees_with_words() -> Result<(), Error> {
+        let english = Alphabet::english();
+        assert_eq!(english.word::<5>("CiGaR")?, Word::try_from("cigar")?);
+        assert_eq!(english.tiles().len(), 26);
+        assert!(matches!(
+            english.word::<5>("añejo"),
+            Err(WordError::Chars(_, 'ñ'))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn tiles_can_be_letters_or_digraphs() -> Result<(), Error> {
+        let spanish: WordN<5> = Alphabet::spanish().word("AÑEJO")?;
+        assert_eq!(spanish.to_string(), "añejo");
+        assert_eq!(spanish.tiles()[1].as_str(), "ñ");
+        let accented: WordN<5> = Alphabet::spanish().word("Jamón")?;
+        assert_ne!(accented, Alphabet::spanish().word("jamon")?);
+
+        let welsh = Alphabet::welsh();
+        let llong: WordN<3> = welsh.word("Llong")?;
+        assert_eq!(llong.tiles().map(|t| t.to_string()), ["ll", "o", "ng"]);
+        assert!(matches!(
+            welsh.word::<5>("llong"),
+            Err(WordError::Length(3, 5))
+        ));
+        assert!(matches!(
+            welsh.word::<3>("kong"),
+            Err(WordError::Chars(_, 'k'))
+        ));
+
+        let guess = WordGuessN::<4>::guess(welsh.word("chwech")?, welsh.word("cwtch")?);
+        assert_eq!(guess.status.to_string(), "⬛🟩⬛🟩");
+        Ok(())
+    }
+
+    #[test]
+    fn alphabets_are_parsed() -> Result<(), Error> {
+        assert_eq!("german".parse::<Alphabet>()?, Alphabet::german());
+        let custom: Alphabet = "a, b, C, ch".parse()?;
+        assert_eq!(custom.to_string(), "a,b,c,ch");
+        assert!("a,b,c4".parse::<Alphabet>().is_err());
+        assert!("a,b,abcde".parse::<Alphabet>().is_err());
+        Ok(())
+    }
+}
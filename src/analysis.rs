@@ -0,0 +1,833 @@
+use crate::matrix::FeedbackMatrix;
+use crate::word_set::WordSet;
+use crate::{GuessStatusN, WordGuessN, WordN};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Formatter};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// A chain of guesses that fits a share, and how many targets it would leave.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Chain<const N: usize = 5> {
+    pub guesses: Vec<WordN<N>>,
+    pub remaining: usize,
+}
+
+/// What one row of a share reveals about the guesses that could have been made.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RowAnalysis<const N: usize = 5> {
+    /// The status shown on this row.
+    pub status: GuessStatusN<N>,
+    /// Every word that gets this status against the target.
+    pub possible_guesses: WordSet<N>,
+    /// How many chains of guesses fit the share up to this row.
+    pub chains: u128,
+    /// The chain leaving the fewest targets, or `None` if no chain fits.
+    pub fewest: Option<Chain<N>>,
+    /// The chain leaving the most targets, or `None` if no chain fits.
+    pub most: Option<Chain<N>>,
+}
+
+impl<const N: usize> RowAnalysis<N> {
+    /// How many targets could be left after this row, from best to worst.
+    pub fn candidate_range(&self) -> Option<RangeInclusive<usize>> {
+        Some(self.fewest.as_ref()?.remaining..=self.most.as_ref()?.remaining)
+    }
+}
+
+/// The possible guesses for one row of a share, merged into classes of guesses that leave the
+/// same targets.
+struct Row<const N: usize> {
+    possible_guesses: WordSet<N>,
+    /// The first guess in each class, in order.
+    first: Vec<WordN<N>>,
+    /// Which classes leave each target.
+    containing: Vec<Vec<u32>>,
+    /// Which classes beat others for the fewest targets left.
+    fewest: Dominance,
+    /// Which classes beat others for the most targets left.
+    most: Dominance,
+}
+
+/// Which classes on a row beat others when looking for the chain leaving the fewest or most
+/// targets.
+///
+/// A class that leaves only some of the targets another leaves does at least as well for the
+/// fewest, and no better for the most, wherever the chain has got to.
+struct Dominance {
+    /// Whether no other class beats each class.
+    kept: Vec<bool>,
+    /// Which of the kept classes leave each target.
+    containing: Vec<Vec<u32>>,
+}
+
+impl<const N: usize> Row<N> {
+    /// Merges the `guesses` with the targets each leaves, given as indices into the targets.
+    fn new(
+        possible_guesses: WordSet<N>,
+        targets: usize,
+        guesses: Vec<(WordN<N>, Vec<u32>)>,
+    ) -> Row<N> {
+        let mut classes: HashMap<Vec<u32>, WordN<N>> = HashMap::new();
+        for (guess, left) in guesses {
+            let first = classes.entry(left).or_insert(guess);
+            *first = guess.min(*first);
+        }
+        let mut classes: Vec<(WordN<N>, Vec<u32>)> =
+            classes.into_iter().map(|(l, w)| (w, l)).collect();
+        classes.sort();
+
+        let mut containing = vec![vec![]; targets];
+        for (j, (_, left)) in classes.iter().enumerate() {
+            for &t in left {
+                containing[t as usize].push(j as u32);
+            }
+        }
+
+        // Every pair of classes where the first leaves only some of the targets the second
+        // does.
+        let mut sets = vec![vec![0u64; targets.div_ceil(64)]; classes.len()];
+        for (set, (_, left)) in sets.iter_mut().zip(&classes) {
+            for &t in left {
+                set[t as usize / 64] |= 1 << (t % 64);
+            }
+        }
+        let subsets: Vec<(u32, u32)> = (0..classes.len())
+            .into_par_iter()
+            .flat_map_iter(|a| {
+                let sets = &sets;
+                let classes = &classes;
+                (a + 1..classes.len()).filter_map(move |b| {
+                    let (fewer, more) = match classes[a].1.len() <= classes[b].1.len() {
+                        true => (a, b),
+                        false => (b, a),
+                    };
+                    (sets[fewer].iter().zip(&sets[more]))
+                        .all(|(fewer, more)| fewer & !more == 0)
+                        .then_some((fewer as u32, more as u32))
+                })
+            })
+            .collect();
+        let most = subsets.iter().map(|&(fewer, more)| (more, fewer));
+        Row {
+            fewest: Dominance::new(&containing, classes.len(), subsets.iter().copied()),
+            most: Dominance::new(&containing, classes.len(), most),
+            possible_guesses,
+            first: classes.into_iter().map(|(guess, _)| guess).collect(),
+            containing,
+        }
+    }
+
+    fn dominance(&self, goal: Goal) -> &Dominance {
+        match goal {
+            Goal::Fewest => &self.fewest,
+            Goal::Most => &self.most,
+        }
+    }
+}
+
+impl Dominance {
+    /// Sorts out which of the `classes` are beaten, given each pair where one beats the other.
+    fn new(
+        containing: &[Vec<u32>],
+        classes: usize,
+        beats: impl Iterator<Item = (u32, u32)>,
+    ) -> Dominance {
+        let mut kept = vec![true; classes];
+        for (_, b) in beats {
+            kept[b as usize] = false;
+        }
+        let containing = containing
+            .iter()
+            .map(|classes| {
+                (classes.iter().copied())
+                    .filter(|&j| kept[j as usize])
+                    .collect()
+            })
+            .collect();
+        Dominance { kept, containing }
+    }
+}
+
+/// Whether a search is for the chain leaving the fewest targets or the most.
+#[derive(Clone, Copy, Debug)]
+enum Goal {
+    Fewest,
+    Most,
+}
+
+impl Goal {
+    /// How good it is to leave `n` targets, where lower is better.
+    fn score(self, n: usize) -> isize {
+        match self {
+            Goal::Fewest => n as isize,
+            Goal::Most => -(n as isize),
+        }
+    }
+}
+
+/// A search for the chain of guesses leaving the fewest or most targets after the last of
+/// some rows.
+///
+/// Each row's guesses are tried a class at a time, and the targets left that no later row
+/// could leave are dropped along the way, so that chains leaving the same targets meet and
+/// are only followed once.  The best score is found first, trying the most promising classes
+/// first and only those that no other beats, and then the first chain in alphabetical order
+/// that gets it.  Chains are given up on once they can't get the score, as worked out by
+/// [`Search::bound`].
+struct Search<'a, const N: usize> {
+    goal: Goal,
+    rows: &'a [Row<N>],
+    /// The targets that some class on each of the rows from here on leaves.
+    leaves: Vec<Vec<bool>>,
+    /// The targets that every class on each of the rows from here on leaves.
+    always: Vec<Vec<bool>>,
+}
+
+impl<'a, const N: usize> Search<'a, N> {
+    fn new(goal: Goal, rows: &'a [Row<N>], targets: usize) -> Search<'a, N> {
+        let mut leaves = vec![vec![true; targets]];
+        let mut always = vec![vec![true; targets]];
+        for row in rows.iter().rev() {
+            let next = (leaves[0].iter().zip(&always[0]).zip(&row.containing)).map(
+                |((&leaves, &always), classes)| {
+                    (
+                        leaves && !classes.is_empty(),
+                        always && classes.len() == row.first.len(),
+                    )
+                },
+            );
+            let (l, a): (Vec<bool>, Vec<bool>) = next.unzip();
+            leaves.insert(0, l);
+            always.insert(0, a);
+        }
+        Search {
+            goal,
+            rows,
+            leaves,
+            always,
+        }
+    }
+
+    /// The targets that might be left at the start of the search.
+    fn start(&self) -> Vec<u32> {
+        (0..self.leaves[0].len() as u32)
+            .filter(|&t| self.leaves[0][t as usize])
+            .collect()
+    }
+
+    /// Which classes on `row` leave each target, either all of them or just those no other
+    /// class beats.
+    fn containing(&self, row: usize, all: bool) -> &[Vec<u32>] {
+        let row = &self.rows[row];
+        if all {
+            &row.containing
+        } else {
+            &row.dominance(self.goal).containing
+        }
+    }
+
+    /// A score no better than any chain from `left` after `depth` rows could get, worked out
+    /// more closely until it's no better than `best`.  On the last row, that's the score
+    /// itself if it's better, and it's `isize::MAX` if no chain goes on from there.
+    ///
+    /// Every target that all the classes on the rows to come leave will be left, and each
+    /// row's class leaving the fewest of them drops at most the rest.  No chain can leave more
+    /// targets than any one row's class leaving the most.
+    fn bound(&self, depth: usize, left: &[u32], best: isize) -> isize {
+        let goal = self.goal;
+        let last = self.rows.len() - 1;
+        let mut bound = match goal {
+            Goal::Fewest => goal.score(
+                (left.iter())
+                    .filter(|&&t| self.always[depth][t as usize])
+                    .count(),
+            ),
+            Goal::Most => goal.score(left.len()),
+        };
+        if bound >= best {
+            return bound;
+        }
+        let mut dropped = 0;
+        for row in depth..=last {
+            let remaining = self.remaining(row, left, false);
+            let kept = remaining
+                .into_iter()
+                .zip(&self.rows[row].dominance(self.goal).kept);
+            let scores = kept.filter(|(_, &kept)| kept).map(|(n, _)| goal.score(n));
+            let Some(score) = scores.min() else {
+                return isize::MAX;
+            };
+            if row == last && depth == last {
+                return score;
+            }
+            match goal {
+                Goal::Fewest => dropped += left.len() - score as usize,
+                Goal::Most => bound = bound.max(score),
+            }
+            if bound >= best {
+                break;
+            }
+        }
+        if let Goal::Fewest = goal {
+            bound = bound.max(goal.score(left.len().saturating_sub(dropped)));
+        }
+        bound
+    }
+
+    /// The targets each class on the `row` leaves out of `left`, or zero for the classes left
+    /// out unless `all` of them are wanted.
+    fn remaining(&self, row: usize, left: &[u32], all: bool) -> Vec<usize> {
+        let containing = self.containing(row, all);
+        let mut remaining = vec![0; self.rows[row].first.len()];
+        for &t in left {
+            for &j in &containing[t as usize] {
+                remaining[j as usize] += 1;
+            }
+        }
+        remaining
+    }
+
+    /// The different sets of targets the classes on the row after `depth` rows leave out of
+    /// `left`, in order, with the first class leaving each, out of all the classes or just
+    /// those no other class beats.
+    ///
+    /// The targets are laid out class by class in one buffer, and only the sets that haven't
+    /// come up already are copied out of it.
+    fn children(&self, depth: usize, left: &[u32], all: bool) -> Vec<(u32, Vec<u32>)> {
+        let containing = self.containing(depth, all);
+        let classes = self.rows[depth].first.len();
+        let kept: Vec<u32> = left
+            .iter()
+            .copied()
+            .filter(|&t| self.leaves[depth + 1][t as usize])
+            .collect();
+        let mut starts = vec![0; classes + 1];
+        for &t in &kept {
+            for &j in &containing[t as usize] {
+                starts[j as usize + 1] += 1;
+            }
+        }
+        for j in 0..classes {
+            starts[j + 1] += starts[j];
+        }
+        let mut ends = starts.clone();
+        let mut buffer = vec![0; starts[classes]];
+        for &t in &kept {
+            for &j in &containing[t as usize] {
+                buffer[ends[j as usize]] = t;
+                ends[j as usize] += 1;
+            }
+        }
+
+        let mut seen: HashSet<&[u32]> = HashSet::new();
+        (0..classes)
+            .filter(|&j| all || self.rows[depth].dominance(self.goal).kept[j])
+            .filter_map(|j| {
+                let child = &buffer[starts[j]..starts[j + 1]];
+                seen.insert(child).then(|| (j as u32, child.to_vec()))
+            })
+            .collect()
+    }
+
+    /// The targets each class on the row after `depth` rows leaves out of `left`, as bits in
+    /// the order of `left`, out of all the classes or just those no other class beats.
+    ///
+    /// Most of the work is on the last two rows, where every class on one meets every class
+    /// on the other, and bits for just the targets left make that quicker than going through
+    /// the targets.
+    fn bits(&self, depth: usize, left: &[u32], all: bool) -> Vec<(u32, Vec<u64>)> {
+        let containing = self.containing(depth, all);
+        let kept = &self.rows[depth].dominance(self.goal).kept;
+        let mut bits = vec![vec![0; left.len().div_ceil(64)]; kept.len()];
+        for (i, &t) in left.iter().enumerate() {
+            for &j in &containing[t as usize] {
+                bits[j as usize][i / 64] |= 1 << (i % 64);
+            }
+        }
+        (0..kept.len() as u32)
+            .zip(bits)
+            .filter(|&(j, _)| all || kept[j as usize])
+            .collect()
+    }
+
+    /// Whether leaving the targets `a` does at least as well as leaving `b`, being only some
+    /// of them for the fewest or all of them and more for the most.
+    fn leaves_beat(&self, a: &[u32], b: &[u32]) -> bool {
+        let (fewer, more) = match self.goal {
+            Goal::Fewest => (a, b),
+            Goal::Most => (b, a),
+        };
+        let mut more = more.iter();
+        fewer.iter().all(|t| more.find(|&m| m >= t) == Some(t))
+    }
+
+    /// Like [`Search::leaves_beat`], for targets as bits.
+    fn beats(&self, a: &[u64], b: &[u64]) -> bool {
+        let (fewer, more) = match self.goal {
+            Goal::Fewest => (a, b),
+            Goal::Most => (b, a),
+        };
+        (fewer.iter().zip(more)).all(|(fewer, more)| fewer & !more == 0)
+    }
+
+    /// The sets in `bits` that no other beats, in the same order, keeping the first of those
+    /// that are the same.
+    fn unbeaten(&self, bits: Vec<(u32, Vec<u64>)>) -> Vec<(u32, Vec<u64>)> {
+        let mut sized: Vec<(isize, (u32, Vec<u64>))> = (bits.into_iter())
+            .map(|(j, set)| {
+                let n = set.iter().map(|block| block.count_ones()).sum::<u32>();
+                (self.goal.score(n as usize), (j, set))
+            })
+            .collect();
+        sized.sort_by_key(|&(score, (j, _))| (score, j));
+        let mut unbeaten: Vec<(u32, Vec<u64>)> = vec![];
+        for (_, (j, set)) in sized {
+            if !unbeaten.iter().any(|(_, other)| self.beats(other, &set)) {
+                unbeaten.push((j, set));
+            }
+        }
+        unbeaten.sort_by_key(|&(j, _)| j);
+        unbeaten
+    }
+
+    /// The scores that each of the `last` classes gets after the targets in `bits`.
+    fn scores<'b>(
+        &self,
+        bits: &'b [u64],
+        last: &'b [(u32, Vec<u64>)],
+    ) -> impl Iterator<Item = (u32, isize)> + Clone + 'b {
+        let goal = self.goal;
+        last.iter().map(move |(j, set)| {
+            let n = (bits.iter().zip(set))
+                .map(|(a, b)| (a & b).count_ones())
+                .sum::<u32>();
+            (*j, goal.score(n as usize))
+        })
+    }
+
+    /// Lowers `best` to the best score any chain from `left` after `depth` rows gets, if
+    /// that's better, trying the most promising classes first.
+    fn best(
+        &self,
+        depth: usize,
+        left: &[u32],
+        best: &mut isize,
+        seen: &mut HashSet<(usize, Vec<u32>)>,
+    ) {
+        let bound = self.bound(depth, left, *best);
+        if bound >= *best {
+            return;
+        }
+        if depth + 1 == self.rows.len() {
+            *best = bound;
+            return;
+        }
+        if depth + 2 == self.rows.len() {
+            let last = self.unbeaten(self.bits(depth + 1, left, false));
+            let scores = (self.unbeaten(self.bits(depth, left, false)).into_par_iter())
+                .filter_map(|(_, bits)| self.scores(&bits, &last).map(|(_, score)| score).min());
+            *best = scores.min().map_or(*best, |score| score.min(*best));
+            return;
+        }
+        let mut children: Vec<Vec<u32>> = (self.children(depth, left, false).into_iter())
+            .map(|(_, child)| child)
+            .collect();
+        children.sort_by_key(|child| self.goal.score(child.len()));
+        let mut unbeaten: Vec<((isize, isize), Vec<u32>)> = vec![];
+        for child in children {
+            if !unbeaten
+                .iter()
+                .any(|(_, other)| self.leaves_beat(other, &child))
+            {
+                let key = (
+                    self.bound(depth + 1, &child, isize::MIN),
+                    self.goal.score(child.len()),
+                );
+                unbeaten.push((key, child));
+            }
+        }
+        unbeaten.sort_by_key(|(key, _)| *key);
+        for (_, child) in unbeaten {
+            if seen.insert((depth + 1, child.clone())) {
+                self.best(depth + 1, &child, best, seen);
+            }
+        }
+    }
+
+    /// Adds the classes of the first chain from `left` after `depth` rows that gets the
+    /// `score` to `chain`, if there is one, which has to be the best score there is.
+    ///
+    /// Once a class gets nowhere, neither do the classes it beats.
+    fn first(
+        &self,
+        score: isize,
+        depth: usize,
+        left: &[u32],
+        chain: &mut Vec<u32>,
+        failed: &mut HashSet<(usize, Vec<u32>)>,
+    ) -> bool {
+        if self.bound(depth, left, score + 1) > score {
+            return false;
+        }
+        if depth + 1 == self.rows.len() {
+            let remaining = self.remaining(depth, left, true);
+            return match remaining.iter().position(|&n| self.goal.score(n) == score) {
+                Some(j) => {
+                    chain.push(j as u32);
+                    true
+                }
+                None => false,
+            };
+        }
+        if depth + 2 == self.rows.len() {
+            let last = self.bits(depth + 1, left, true);
+            let unbeaten = self.unbeaten(self.bits(depth + 1, left, false));
+            let mut failed: Vec<Vec<u64>> = vec![];
+            for (j, bits) in self.bits(depth, left, true) {
+                if failed.iter().any(|f| self.beats(f, &bits)) {
+                    continue;
+                }
+                let best = self.scores(&bits, &unbeaten).map(|(_, score)| score).min();
+                if best == Some(score) {
+                    if let Some((k, _)) = self.scores(&bits, &last).find(|&(_, s)| s == score) {
+                        chain.extend([j, k]);
+                        return true;
+                    }
+                }
+                failed.push(bits);
+            }
+            return false;
+        }
+        let mut beaten: Vec<Vec<u32>> = vec![];
+        for (j, child) in self.children(depth, left, true) {
+            if beaten.iter().any(|b| self.leaves_beat(b, &child)) {
+                continue;
+            }
+            let key = (depth + 1, child);
+            if !failed.contains(&key) {
+                chain.push(j);
+                if self.first(score, depth + 1, &key.1, chain, failed) {
+                    return true;
+                }
+                chain.pop();
+                failed.insert(key.clone());
+            }
+            beaten.push(key.1);
+        }
+        false
+    }
+
+    /// The first chain in alphabetical order of those getting the best score.
+    fn chain(&self) -> Option<Chain<N>> {
+        let start = self.start();
+        let mut best = isize::MAX;
+        self.best(0, &start, &mut best, &mut HashSet::new());
+        if best == isize::MAX {
+            return None;
+        }
+        let mut classes = vec![];
+        if !self.first(best, 0, &start, &mut classes, &mut HashSet::new()) {
+            return None;
+        }
+        Some(Chain {
+            guesses: (self.rows.iter().zip(classes))
+                .map(|(row, j)| row.first[j as usize])
+                .collect(),
+            remaining: best.unsigned_abs(),
+        })
+    }
+}
+
+/// Works back from a shared result to the guesses that could have produced it, one row at a
+/// time.
+///
+/// Guesses that leave the same targets are interchangeable, so each row's guesses are merged
+/// into classes of those, and the chains leaving the fewest and most targets are searched for
+/// a class at a time rather than trying every chain.
+pub struct Analysis<const N: usize = 5> {
+    target: WordN<N>,
+    guesses: WordSet<N>,
+    targets: Vec<WordN<N>>,
+    statuses: Vec<GuessStatusN<N>>,
+    rows: Vec<Row<N>>,
+    /// A matrix with every target, along with where each target is in it.
+    matrix: Option<(Arc<FeedbackMatrix<N>>, Vec<usize>)>,
+}
+
+impl<const N: usize> Debug for Analysis<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Analysis({}, row {} of {})",
+            self.target,
+            self.rows.len(),
+            self.statuses.len()
+        ))
+    }
+}
+
+impl<const N: usize> Analysis<N> {
+    /// Starts analysing a share for `target` with rows showing the `statuses`, where any of
+    /// `guesses` might have been played and any of `targets` might have been the answer.
+    pub fn new(
+        target: WordN<N>,
+        guesses: WordSet<N>,
+        targets: WordSet<N>,
+        statuses: &[GuessStatusN<N>],
+    ) -> Analysis<N> {
+        Analysis {
+            target,
+            guesses,
+            targets: targets.iter().collect(),
+            statuses: statuses.to_vec(),
+            rows: vec![],
+            matrix: None,
+        }
+    }
+
+    /// Look statuses up in `matrix` rather than working them out, if it has every target.
+    pub fn with_matrix(self, matrix: Arc<FeedbackMatrix<N>>) -> Analysis<N> {
+        let indices: Option<Vec<usize>> = self
+            .targets
+            .iter()
+            .map(|&target| matrix.target_index(target))
+            .collect();
+        Analysis {
+            matrix: indices.map(|indices| (matrix, indices)),
+            ..self
+        }
+    }
+
+    /// Where the targets that give `guess` the `status` are in the targets.
+    fn matching(&self, guess: WordN<N>, status: GuessStatusN<N>) -> Vec<u32> {
+        if let Some((matrix, indices)) = &self.matrix {
+            if let Some(index) = matrix.guess_index(guess) {
+                let row = matrix.row(index);
+                let code = status.index() as u8;
+                return (0..indices.len() as u32)
+                    .filter(|&i| row[indices[i as usize]] == code)
+                    .collect();
+            }
+        }
+        (0..self.targets.len() as u32)
+            .filter(|&i| WordGuessN::guess_from(guess, &self.targets[i as usize]).status == status)
+            .collect()
+    }
+
+    /// The guesses that get `status` against the target, and the targets each leaves.
+    fn row(&self, status: GuessStatusN<N>) -> Row<N> {
+        let target = self.target;
+        let possible_guesses = self
+            .guesses
+            .filter(|&w| WordGuessN::guess(w, target).status == status);
+        let guesses = possible_guesses
+            .iter()
+            .collect::<Vec<WordN<N>>>()
+            .into_par_iter()
+            .map(|guess| (guess, self.matching(guess, status)))
+            .collect();
+        Row::new(possible_guesses, self.targets.len(), guesses)
+    }
+}
+
+impl<const N: usize> Iterator for Analysis<N> {
+    type Item = RowAnalysis<N>;
+
+    /// Analyses the next row of the share.
+    fn next(&mut self) -> Option<RowAnalysis<N>> {
+        let status = *self.statuses.get(self.rows.len())?;
+        let row = self.row(status);
+        let possible_guesses = row.possible_guesses.clone();
+        self.rows.push(row);
+
+        let search = |goal| Search::new(goal, &self.rows, self.targets.len()).chain();
+        let (fewest, most) = rayon::join(|| search(Goal::Fewest), || search(Goal::Most));
+        Some(RowAnalysis {
+            status,
+            possible_guesses,
+            chains: (self.rows.iter())
+                .map(|row| row.possible_guesses.len() as u128)
+                .product(),
+            fewest,
+            most,
+        })
+    }
+}
+
+/// Analyses every row of a share for `target`, with the statuses in the order they were shown.
+pub fn analyse<const N: usize>(
+    target: WordN<N>,
+    guesses: WordSet<N>,
+    targets: WordSet<N>,
+    statuses: &[GuessStatusN<N>],
+) -> Vec<RowAnalysis<N>> {
+    Analysis::new(target, guesses, targets, statuses).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::{analyse, Analysis, Row};
+    use crate::matrix::FeedbackMatrix;
+    use crate::share::Share;
+    use crate::word_list::WordList;
+    use crate::word_set::WordSet;
+    use crate::words::TARGET_WORDS;
+    use crate::{Word, WordGuess, WordGuessN, WordN};
+    use anyhow::Error;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn analysis_follows_the_game() -> Result<(), Error> {
+        let target = Word::try_from("those")?;
+        let played = ["raise", "cloth", "those"]
+            .iter()
+            .map(|&w| Ok(WordGuess::guess(Word::try_from(w)?, target)))
+            .collect::<Result<Vec<WordGuess>, Error>>()?;
+        let statuses: Vec<_> = played.iter().map(|wg| wg.status).collect();
+        let rows = analyse(target, WordSet::all(), WordSet::targets(), &statuses);
+        assert_eq!(rows.len(), 3);
+        for (i, (row, wg)) in rows.iter().zip(&played).enumerate() {
+            assert_eq!(row.status, wg.status);
+            assert!(row.possible_guesses.contains(&wg.word()));
+            let range = row.candidate_range().unwrap();
+            assert!(*range.start() >= 1);
+            assert_eq!(row.fewest.as_ref().unwrap().guesses.len(), i + 1);
+            assert_eq!(row.most.as_ref().unwrap().guesses.len(), i + 1);
+        }
+        assert_eq!(rows[2].candidate_range(), Some(1..=1));
+        Ok(())
+    }
+
+    #[test]
+    fn guesses_leaving_the_same_targets_merge() -> Result<(), Error> {
+        let guesses = ["rebut", "sissy", "cigar", "humph", "those"]
+            .iter()
+            .zip([vec![0, 2], vec![1], vec![0, 2], vec![], vec![0, 1, 2]])
+            .map(|(&w, left)| Ok((Word::try_from(w)?, left)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let row = Row::new(WordSet::new(), 3, guesses);
+        let first: Vec<String> = row.first.iter().map(|w| w.to_string()).collect();
+        assert_eq!(first, ["cigar", "humph", "sissy", "those"]);
+        assert_eq!(row.containing, [vec![0, 3], vec![2, 3], vec![0, 3]]);
+        assert_eq!(row.fewest.kept, [false, true, false, false]);
+        assert_eq!(row.most.kept, [false, false, false, true]);
+        Ok(())
+    }
+
+    /// Every chain through the rows for the statuses `target` gets from `played`, with the number
+    /// of `words` it leaves.
+    fn every_chain<const N: usize>(
+        words: &WordSet<N>,
+        target: WordN<N>,
+        played: &[WordN<N>],
+    ) -> Vec<(usize, Vec<WordN<N>>)> {
+        let mut chains = vec![(words.clone(), vec![])];
+        for &guess in played {
+            let status = WordGuessN::guess(guess, target).status;
+            let row = words.filter(|&w| WordGuessN::guess(w, target).status == status);
+            chains = (chains.iter())
+                .flat_map(|(left, path)| {
+                    row.iter().map(move |w| {
+                        let left = left.filter(|t| WordGuessN::guess_from(w, t).status == status);
+                        (left, path.iter().copied().chain([w]).collect())
+                    })
+                })
+                .collect();
+        }
+        (chains.into_iter())
+            .map(|(left, path)| (left.len(), path))
+            .collect()
+    }
+
+    fn assert_matches_every_chain<const N: usize>(
+        words: &WordSet<N>,
+        target: WordN<N>,
+        played: &[WordN<N>],
+    ) {
+        let statuses: Vec<_> = (played.iter())
+            .map(|&guess| WordGuessN::guess(guess, target).status)
+            .collect();
+        let row = Analysis::new(target, words.clone(), words.clone(), &statuses)
+            .last()
+            .unwrap();
+        let chains = every_chain(words, target, played);
+        assert_eq!(row.chains, chains.len() as u128);
+        let fewest = chains.iter().min().unwrap();
+        assert_eq!(row.fewest.as_ref().unwrap().remaining, fewest.0);
+        assert_eq!(row.fewest.as_ref().unwrap().guesses, fewest.1);
+        let most = chains.iter().map(|(n, _)| n).max().unwrap();
+        let most = chains.iter().filter(|(n, _)| n == most).min().unwrap();
+        assert_eq!(row.most.as_ref().unwrap().remaining, most.0);
+        assert_eq!(row.most.as_ref().unwrap().guesses, most.1);
+    }
+
+    #[test]
+    fn searched_chains_match_every_chain() -> Result<(), Error> {
+        let words =
+            WordSet::from_words(&WordList::embedded(), TARGET_WORDS[..300].iter().copied())?;
+        let played = [TARGET_WORDS[100], TARGET_WORDS[200]];
+        assert_matches_every_chain(&words, TARGET_WORDS[7], &played);
+
+        let words = WordSet::from_words(&WordList::embedded(), TARGET_WORDS[..60].iter().copied())?;
+        let played = [TARGET_WORDS[10], TARGET_WORDS[20], TARGET_WORDS[30]];
+        assert_matches_every_chain(&words, TARGET_WORDS[7], &played);
+        Ok(())
+    }
+
+    #[test]
+    fn other_lengths_are_analysed() -> Result<(), Error> {
+        let list = Arc::new(WordList::<4>::parse(
+            "bark cork dork fork lark park pork work barn corn dorm form",
+        )?);
+        let words = WordSet::full(&list);
+        let played = [list.word("park")?, list.word("form")?];
+        assert_matches_every_chain(&words, list.word("work")?, &played);
+        Ok(())
+    }
+
+    #[test]
+    fn six_row_shares_are_analysed_quickly() -> Result<(), Error> {
+        let share: Share = "Wordle 227 6/6\n\n\
+            ⬛⬛⬛⬛⬛\n\
+            ⬛⬛⬛⬛🟨\n\
+            ⬛🟨⬛⬛⬛\n\
+            🟨🟨⬛🟩🟨\n\
+            ⬛🟩🟩🟩🟨\n\
+            🟩🟩🟩🟩🟩"
+            .parse()?;
+        let start = Instant::now();
+        let rows = analyse(
+            Word::try_from("those")?,
+            WordSet::all(),
+            WordSet::targets(),
+            &share.rows,
+        );
+        assert!(start.elapsed() < Duration::from_secs(60));
+        assert_eq!(rows.len(), 6);
+        assert!(rows.iter().all(|row| row.candidate_range().is_some()));
+        assert_eq!(rows[5].candidate_range(), Some(1..=1));
+        Ok(())
+    }
+
+    #[test]
+    fn matrix_gives_the_same_analysis() -> Result<(), Error> {
+        let words =
+            WordSet::from_words(&WordList::embedded(), TARGET_WORDS[..300].iter().copied())?;
+        let target = TARGET_WORDS[7];
+        let matrix = Arc::new(FeedbackMatrix::build(
+            &TARGET_WORDS[..300],
+            &TARGET_WORDS[..300],
+        ));
+        let statuses: Vec<_> = [TARGET_WORDS[100], TARGET_WORDS[200]]
+            .iter()
+            .map(|&guess| WordGuess::guess(guess, target).status)
+            .collect();
+        let plain = Analysis::new(target, words.clone(), words.clone(), &statuses);
+        let fast = Analysis::new(target, words.clone(), words, &statuses).with_matrix(matrix);
+        assert!(fast.matrix.is_some());
+        assert!(fast.eq(plain));
+        Ok(())
+    }
+}
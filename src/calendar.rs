@@ -0,0 +1,109 @@
+use crate::WordError;
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, TimeZone, Utc};
+use std::str::FromStr;
+
+/// The day of the first puzzle, number 0.
+pub const FIRST_DAY: NaiveDate = match NaiveDate::from_ymd_opt(2021, 6, 19) {
+    Some(date) => date,
+    None => panic!("The first puzzle's date is not a real date"),
+};
+
+/// The number of the puzzle for `date`, or an error if it's before the first puzzle.
+pub fn puzzle_for_date(date: NaiveDate) -> Result<usize, WordError> {
+    usize::try_from((date - FIRST_DAY).num_days()).map_err(|_| WordError::Date(date))
+}
+
+/// The day that puzzle number `puzzle` was set for.
+pub fn date_for_puzzle(puzzle: usize) -> NaiveDate {
+    FIRST_DAY + Duration::days(puzzle as i64)
+}
+
+/// Where the days are counted, as the puzzle changes at midnight wherever it's being played.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Zone {
+    /// The system's own time zone.
+    #[default]
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// The date in this zone at `instant`.
+    pub fn date_at<Tz: TimeZone>(self, instant: &DateTime<Tz>) -> NaiveDate {
+        match self {
+            Zone::Local => instant.with_timezone(&Local).date_naive(),
+            Zone::Fixed(offset) => instant.with_timezone(&offset).date_naive(),
+        }
+    }
+
+    pub fn today(self) -> NaiveDate {
+        self.date_at(&Utc::now())
+    }
+
+    /// The number of the puzzle being played in this zone at `instant`.
+    pub fn puzzle_at<Tz: TimeZone>(self, instant: &DateTime<Tz>) -> Result<usize, WordError> {
+        puzzle_for_date(self.date_at(instant))
+    }
+}
+
+/// Parses `local`, `utc`, or an offset from UTC like `+05:30` or `-0800`.
+impl FromStr for Zone {
+    type Err = WordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || WordError::Zone(s.to_string());
+        match s.to_ascii_lowercase().as_str() {
+            "local" => return Ok(Zone::Local),
+            "utc" | "z" => return Ok(Zone::Fixed(FixedOffset::east_opt(0).ok_or_else(error)?)),
+            _ => {}
+        }
+        let (sign, offset) = match (s.strip_prefix('+'), s.strip_prefix('-')) {
+            (Some(offset), _) => (1, offset),
+            (_, Some(offset)) => (-1, offset),
+            _ => return Err(error()),
+        };
+        let digits: String = offset.chars().filter(|&c| c != ':').collect();
+        if !(digits.len() == 2 || digits.len() == 4) || !digits.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(error());
+        }
+        let hours: i32 = digits[..2].parse().map_err(|_| error())?;
+        let minutes: i32 = digits[2..].parse().unwrap_or(0);
+        FixedOffset::east_opt(sign * (hours * 60 + minutes) * 60)
+            .map(Zone::Fixed)
+            .ok_or_else(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::calendar::{date_for_puzzle, puzzle_for_date, Zone, FIRST_DAY};
+    use crate::WordError;
+    use anyhow::Error;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn dates_map_to_puzzles() -> Result<(), Error> {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(puzzle_for_date(FIRST_DAY)?, 0);
+        assert_eq!(FIRST_DAY, date(2021, 6, 19));
+        assert_eq!(puzzle_for_date(date(2022, 2, 5))?, 231);
+        assert_eq!(date_for_puzzle(231), date(2022, 2, 5));
+        assert!(matches!(
+            puzzle_for_date(date(2021, 6, 18)),
+            Err(WordError::Date(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn puzzles_change_at_midnight_in_the_zone() -> Result<(), Error> {
+        let instant = Utc.with_ymd_and_hms(2022, 2, 5, 23, 30, 0).unwrap();
+        assert_eq!("utc".parse::<Zone>()?.puzzle_at(&instant)?, 231);
+        assert_eq!("+01:00".parse::<Zone>()?.puzzle_at(&instant)?, 232);
+        assert_eq!("-0800".parse::<Zone>()?.puzzle_at(&instant)?, 231);
+        assert!("+1".parse::<Zone>().is_err());
+        assert!("Europe/London".parse::<Zone>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::hard_mode::Ordinal;
+use crate::{GuessStatusN, LetterGuess, Palette, Tile, WordError, WordGuessN};
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+/// How to show a status, or a guess along with its status.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Format {
+    /// `=+-`, as statuses are typed in.
+    Ascii,
+    /// Coloured squares, as the game shares them.
+    Emoji(Palette),
+    /// The names of the squares, as Slack writes them.  Discord's names, like `:green_square:`,
+    /// are read too, but not shown.
+    Shortcodes(Palette),
+    /// The letters of the guess, coloured for a terminal.
+    Ansi,
+    /// A description for screen readers, like "C correct, I misplaced, G absent".
+    Text,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Emoji(Palette::default())
+    }
+}
+
+/// Displays a status, guess, or something made of them, in a [`Format`].
+#[derive(Copy, Clone, Debug)]
+pub struct InFormat<T>(pub T, pub Format);
+
+/// Shows `letter` on a tile coloured for `status`, or a plain bold tile if there's no status.
+pub fn paint(letter: &str, status: Option<LetterGuess>) -> String {
+    let colour = match status {
+        Some(LetterGuess::Correct) => "\x1b[1;30;42m",
+        Some(LetterGuess::Misplaced) => "\x1b[1;30;43m",
+        Some(LetterGuess::NotUsed) => "\x1b[1;37;100m",
+        None => "\x1b[1m",
+    };
+    format!("{} {} \x1b[0m", colour, letter.to_uppercase())
+}
+
+fn shortcode(palette: Palette, status: LetterGuess) -> &'static str {
+    match palette.square(status) {
+        '🟩' => ":large_green_square:",
+        '🟨' => ":large_yellow_square:",
+        '🟧' => ":large_orange_square:",
+        '🟦' => ":large_blue_square:",
+        '⬜' => ":white_large_square:",
+        _ => ":black_large_square:",
+    }
+}
+
+fn describe(status: LetterGuess) -> &'static str {
+    match status {
+        LetterGuess::Correct => "correct",
+        LetterGuess::Misplaced => "misplaced",
+        LetterGuess::NotUsed => "absent",
+    }
+}
+
+impl Format {
+    fn write_status<const N: usize>(
+        self,
+        f: &mut Formatter<'_>,
+        status: GuessStatusN<N>,
+        letters: Option<[Tile; N]>,
+    ) -> std::fmt::Result {
+        for (i, &s) in status.0.iter().enumerate() {
+            let letter = letters.map(|letters| letters[i]);
+            match self {
+                Format::Ascii => f.write_char(match s {
+                    LetterGuess::Correct => '=',
+                    LetterGuess::Misplaced => '+',
+                    LetterGuess::NotUsed => '-',
+                })?,
+                Format::Emoji(palette) => f.write_char(palette.square(s))?,
+                Format::Shortcodes(palette) => f.write_str(shortcode(palette, s))?,
+                Format::Ansi => {
+                    f.write_str(&paint(letter.as_ref().map_or(" ", Tile::as_str), Some(s)))?
+                }
+                Format::Text => {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    match letter {
+                        Some(letter) => write!(f, "{}", letter.as_str().to_uppercase())?,
+                        None => write!(f, "{}", Ordinal(i))?,
+                    }
+                    write!(f, " {}", describe(s))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> GuessStatusN<N> {
+    pub fn in_format(self, format: Format) -> InFormat<GuessStatusN<N>> {
+        InFormat(self, format)
+    }
+}
+
+impl<const N: usize> Display for InFormat<GuessStatusN<N>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.1.write_status(f, self.0, None)
+    }
+}
+
+impl<const N: usize> WordGuessN<N> {
+    pub fn in_format(self, format: Format) -> InFormat<WordGuessN<N>> {
+        InFormat(self, format)
+    }
+}
+
+/// The letters are shown by the ANSI and text formats, and written before the status by the
+/// others.
+impl<const N: usize> Display for InFormat<WordGuessN<N>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let InFormat(wg, format) = *self;
+        match format {
+            Format::Ansi | Format::Text => format.write_status(f, wg.status, Some(wg.word().0)),
+            _ => {
+                write!(f, "{} ", wg.word())?;
+                format.write_status(f, wg.status, None)
+            }
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = WordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dark" => Ok(Palette::Dark),
+            "light" => Ok(Palette::Light),
+            "high-contrast" => Ok(Palette::HighContrast),
+            "high-contrast-light" => Ok(Palette::HighContrastLight),
+            _ => Err(WordError::Format(s.to_string())),
+        }
+    }
+}
+
+/// Parses `ascii`, `ansi`, `text`, or `emoji` or `shortcodes` optionally followed by a palette,
+/// like `emoji-high-contrast`.
+impl FromStr for Format {
+    type Err = WordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let palette = |rest: &str| match rest.strip_prefix('-') {
+            Some(palette) => palette
+                .parse()
+                .map_err(|_| WordError::Format(s.to_string())),
+            None if rest.is_empty() => Ok(Palette::default()),
+            None => Err(WordError::Format(s.to_string())),
+        };
+        match s {
+            "ascii" => Ok(Format::Ascii),
+            "ansi" => Ok(Format::Ansi),
+            "text" => Ok(Format::Text),
+            _ => {
+                if let Some(rest) = s.strip_prefix("emoji") {
+                    Ok(Format::Emoji(palette(rest)?))
+                } else if let Some(rest) = s.strip_prefix("shortcodes") {
+                    Ok(Format::Shortcodes(palette(rest)?))
+                } else {
+                    Err(WordError::Format(s.to_string()))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::format::Format;
+    use crate::{GuessStatus, Palette, Word, WordGuess};
+    use anyhow::Error;
+
+    #[test]
+    fn statuses_are_formatted() -> Result<(), Error> {
+        let status = GuessStatus::try_from("=+---")?;
+        let shown = |format: &str| -> Result<String, Error> {
+            Ok(status.in_format(format.parse()?).to_string())
+        };
+        assert_eq!(shown("ascii")?, "=+---");
+        assert_eq!(shown("emoji")?, "🟩🟨⬛⬛⬛");
+        assert_eq!(shown("emoji-high-contrast-light")?, "🟧🟦⬜⬜⬜");
+        assert_eq!(
+            shown("shortcodes-light")?,
+            ":large_green_square::large_yellow_square::white_large_square:\
+            :white_large_square::white_large_square:"
+        );
+        assert_eq!(
+            shown("text")?,
+            "1st correct, 2nd misplaced, 3rd absent, 4th absent, 5th absent"
+        );
+        for format in ["ascii", "emoji-light", "shortcodes-high-contrast"] {
+            assert_eq!(GuessStatus::try_from(shown(format)?.as_str())?, status);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn guesses_are_formatted() -> Result<(), Error> {
+        let wg = WordGuess::guess(Word::try_from("cigar")?, Word::try_from("chair")?);
+        assert_eq!(
+            wg.in_format(Format::Text).to_string(),
+            "C correct, I misplaced, G absent, A misplaced, R correct"
+        );
+        assert_eq!(wg.in_format(Format::Ascii).to_string(), "cigar =+-+=");
+        assert_eq!(
+            wg.in_format(Format::Ansi).to_string(),
+            "\x1b[1;30;42m C \x1b[0m\x1b[1;30;43m I \x1b[0m\x1b[1;37;100m G \x1b[0m\
+            \x1b[1;30;43m A \x1b[0m\x1b[1;30;42m R \x1b[0m"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_formats_are_rejected() {
+        assert!("emoji-sepia".parse::<Format>().is_err());
+        assert!("emojis".parse::<Format>().is_err());
+        assert_eq!(
+            "shortcodes".parse::<Format>().ok(),
+            Some(Format::Shortcodes(Palette::Dark))
+        );
+    }
+}
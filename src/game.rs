@@ -0,0 +1,179 @@
+use crate::hard_mode::{self, HardModeViolation};
+use crate::word_list::WordList;
+use crate::{Word, WordError, WordGuessN, WordN};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// A single game against a known target, keeping track of the rules.
+#[derive(Clone, Debug)]
+pub struct Game<const N: usize = 5> {
+    target: WordN<N>,
+    guesses: Vec<WordGuessN<N>>,
+    max_guesses: usize,
+    hard_mode: bool,
+    /// The words that can be guessed.
+    words: Arc<WordList<N>>,
+}
+
+#[derive(Debug, Error)]
+pub enum GameError<const N: usize = 5> {
+    #[error("The game is over")]
+    Over,
+    #[error(transparent)]
+    InvalidWord(#[from] WordError),
+    #[error("{0} has already been guessed")]
+    Repeated(WordN<N>),
+    #[error(transparent)]
+    HardMode(#[from] HardModeViolation),
+}
+
+impl Game {
+    /// A game whose guesses have to be on the built-in lists.
+    pub fn new(target: Word, max_guesses: usize, hard_mode: bool) -> Game {
+        Game::with_words(target, WordList::embedded(), max_guesses, hard_mode)
+    }
+}
+
+impl<const N: usize> Game<N> {
+    /// A game whose guesses have to be on `words`, spelled in its alphabet.
+    pub fn with_words(
+        target: WordN<N>,
+        words: Arc<WordList<N>>,
+        max_guesses: usize,
+        hard_mode: bool,
+    ) -> Game<N> {
+        Game {
+            target,
+            guesses: vec![],
+            max_guesses,
+            hard_mode,
+            words,
+        }
+    }
+
+    /// Guesses `word`, returning the status it gets.
+    ///
+    /// Guesses that are rejected don't count towards the limit.
+    pub fn submit(&mut self, word: &str) -> Result<WordGuessN<N>, GameError<N>> {
+        if self.is_over() {
+            return Err(GameError::Over);
+        }
+        let word = self.words.word(word)?;
+        if self.guesses.iter().any(|wg| wg.word() == word) {
+            return Err(GameError::Repeated(word));
+        }
+        if self.hard_mode {
+            hard_mode::check(&self.guesses, word)?;
+        }
+        let wg = WordGuessN::guess(word, self.target);
+        self.guesses.push(wg);
+        Ok(wg)
+    }
+
+    pub fn target(&self) -> WordN<N> {
+        self.target
+    }
+
+    pub fn guesses(&self) -> &[WordGuessN<N>] {
+        &self.guesses
+    }
+
+    pub fn max_guesses(&self) -> usize {
+        self.max_guesses
+    }
+
+    pub fn hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.guesses.last().map(|wg| wg.word()) == Some(self.target)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guesses.len() >= self.max_guesses
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::{Game, GameError};
+    use crate::hard_mode::HardModeViolation;
+    use crate::word_list::WordList;
+    use crate::{GuessStatusN, Word, WordError};
+    use anyhow::Error;
+    use std::sync::Arc;
+
+    #[test]
+    fn game_is_won() -> Result<(), Error> {
+        let mut game = Game::new(Word::try_from("those")?, 6, false);
+        game.submit("cigar")?;
+        assert!(!game.is_over());
+        game.submit("those")?;
+        assert!(game.is_won());
+        assert!(matches!(game.submit("rebut"), Err(GameError::Over)));
+        Ok(())
+    }
+
+    #[test]
+    fn game_is_lost() -> Result<(), Error> {
+        let mut game = Game::new(Word::try_from("those")?, 2, false);
+        game.submit("cigar")?;
+        game.submit("rebut")?;
+        assert!(game.is_over());
+        assert!(!game.is_won());
+        assert!(matches!(game.submit("those"), Err(GameError::Over)));
+        Ok(())
+    }
+
+    #[test]
+    fn rejected_guesses_do_not_count() -> Result<(), Error> {
+        let mut game = Game::new(Word::try_from("those")?, 6, false);
+        game.submit("cigar")?;
+        assert!(matches!(
+            game.submit("cigars"),
+            Err(GameError::InvalidWord(_))
+        ));
+        assert!(matches!(game.submit("cigar"), Err(GameError::Repeated(_))));
+        assert_eq!(game.guesses().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn guesses_come_from_the_words_given() -> Result<(), Error> {
+        let words = Arc::new(WordList::parse("cigar rebut qxzvj those")?);
+        let mut game = Game::with_words(Word::try_from("those")?, words, 6, false);
+        game.submit("qxzvj")?;
+        assert!(matches!(
+            game.submit("sissy"),
+            Err(GameError::InvalidWord(WordError::NotWord(_)))
+        ));
+        game.submit("those")?;
+        assert!(game.is_won());
+
+        let words = Arc::new(WordList::<4>::parse("bark cork dork fork")?);
+        let mut game = Game::with_words(words.word("fork")?, words.clone(), 2, false);
+        assert!(matches!(
+            game.submit("forks"),
+            Err(GameError::InvalidWord(WordError::Length(5, 4)))
+        ));
+        assert_eq!(game.submit("cork")?.status, GuessStatusN::try_from("-===")?);
+        game.submit("fork")?;
+        assert!(game.is_won());
+        Ok(())
+    }
+
+    #[test]
+    fn hard_mode_requires_hints() -> Result<(), Error> {
+        let mut game = Game::new(Word::try_from("those")?, 6, true);
+        game.submit("house")?;
+        assert!(matches!(game.submit("cigar"), Err(GameError::HardMode(_))));
+        assert!(matches!(
+            game.submit("mouse"),
+            Err(GameError::HardMode(HardModeViolation::Missing { letter }))
+                if letter.as_str() == "h"
+        ));
+        game.submit("horse")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{LetterGuess, Tile, WordGuessN, WordN};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// The hard mode rule a guess broke, worded the way the game itself words it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Error)]
+pub enum HardModeViolation {
+    /// A letter that was correct has been moved or dropped.
+    #[error("{} letter must be {}", Ordinal(*position), letter.as_str().to_uppercase())]
+    Moved { position: usize, letter: Tile },
+    /// A letter that was misplaced, or a repeat of one that was correct, has been dropped.
+    #[error("Guess must contain {}", letter.as_str().to_uppercase())]
+    Missing { letter: Tile },
+}
+
+pub(crate) struct Ordinal(pub(crate) usize);
+
+impl Display for Ordinal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let n = self.0 + 1;
+        let suffix = match (n % 10, n % 100) {
+            (1, 11) | (2, 12) | (3, 13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        write!(f, "{}{}", n, suffix)
+    }
+}
+
+/// Checks that `guess` uses every hint revealed by `history`: correct letters stay where they
+/// are, and misplaced letters are used again.
+pub fn check<const N: usize>(
+    history: &[WordGuessN<N>],
+    guess: WordN<N>,
+) -> Result<(), HardModeViolation> {
+    for wg in history {
+        let previous = wg.word();
+        for (position, ((&p, &g), status)) in
+            previous.0.iter().zip(&guess.0).zip(wg.status.0).enumerate()
+        {
+            if status == LetterGuess::Correct && p != g {
+                return Err(HardModeViolation::Moved {
+                    position,
+                    letter: p,
+                });
+            }
+        }
+        for (&p, status) in previous.0.iter().zip(wg.status.0) {
+            if status == LetterGuess::NotUsed {
+                continue;
+            }
+            let required = previous
+                .0
+                .iter()
+                .zip(wg.status.0)
+                .filter(|&(&l, s)| l == p && s != LetterGuess::NotUsed)
+                .count();
+            if guess.0.iter().filter(|&&l| l == p).count() < required {
+                return Err(HardModeViolation::Missing { letter: p });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finds the first guess in a recorded game that broke the hard mode rules, if any, along
+/// with its index in `history`.
+pub fn first_violation<const N: usize>(
+    history: &[WordGuessN<N>],
+) -> Option<(usize, HardModeViolation)> {
+    (1..history.len()).find_map(|i| {
+        check(&history[..i], history[i].word())
+            .err()
+            .map(|violation| (i, violation))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hard_mode::{check, first_violation, HardModeViolation};
+    use crate::{Tile, Word, WordGuess};
+    use anyhow::Error;
+
+    fn history(words: &[&str], target: &str) -> Result<Vec<WordGuess>, Error> {
+        let target = Word::try_from(target)?;
+        words
+            .iter()
+            .map(|&w| Ok(WordGuess::guess(Word::try_from(w)?, target)))
+            .collect()
+    }
+
+    #[test]
+    fn correct_letters_must_stay() -> Result<(), Error> {
+        let history = history(&["house"], "those")?;
+        let violation = check(&history, Word::try_from("hoses")?).unwrap_err();
+        assert_eq!(
+            violation,
+            HardModeViolation::Moved {
+                position: 3,
+                letter: Tile::new("s")?
+            }
+        );
+        assert_eq!(violation.to_string(), "4th letter must be S");
+        Ok(())
+    }
+
+    #[test]
+    fn misplaced_letters_must_be_used() -> Result<(), Error> {
+        let history = history(&["house"], "those")?;
+        let violation = check(&history, Word::try_from("mouse")?).unwrap_err();
+        assert_eq!(
+            violation,
+            HardModeViolation::Missing {
+                letter: Tile::new("h")?
+            }
+        );
+        assert_eq!(violation.to_string(), "Guess must contain H");
+        assert!(check(&history, Word::try_from("horse")?).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_letters_must_all_be_used() -> Result<(), Error> {
+        let history = history(&["geese"], "eerie")?;
+        assert_eq!(check(&history, Word::try_from("eerie")?), Ok(()));
+        assert_eq!(
+            check(&history, Word::try_from("beige")?),
+            Err(HardModeViolation::Missing {
+                letter: Tile::new("e")?
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn recorded_games_are_checked() -> Result<(), Error> {
+        assert_eq!(
+            first_violation(&history(&["raise", "house", "those"], "those")?),
+            None
+        );
+        assert_eq!(
+            first_violation(&history(&["raise", "cloth", "those"], "those")?),
+            Some((
+                1,
+                HardModeViolation::Moved {
+                    position: 3,
+                    letter: Tile::new("s")?
+                }
+            ))
+        );
+        Ok(())
+    }
+}
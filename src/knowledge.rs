@@ -0,0 +1,147 @@
+use crate::{LetterGuess, Tile, WordGuessN, WordN};
+
+/// Everything a history of guesses reveals about the target, folded into per-position and
+/// per-tile constraints.
+///
+/// [`Knowledge::matches`] agrees with re-scoring a word against every guess in the history,
+/// but only has to look at the word once.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Knowledge<const N: usize = 5> {
+    correct: [Option<Tile>; N],
+    excluded: [Vec<Tile>; N],
+    /// The fewest and most of each guessed tile the target can have.
+    counts: Vec<(Tile, u8, u8)>,
+    impossible: bool,
+}
+
+impl<const N: usize> Default for Knowledge<N> {
+    fn default() -> Self {
+        Knowledge {
+            correct: [None; N],
+            excluded: std::array::from_fn(|_| vec![]),
+            counts: vec![],
+            impossible: false,
+        }
+    }
+}
+
+impl<const N: usize> Knowledge<N> {
+    pub fn from_history(history: &[WordGuessN<N>]) -> Knowledge<N> {
+        let mut knowledge = Knowledge::default();
+        history.iter().for_each(|wg| knowledge.add(wg));
+        knowledge
+    }
+
+    /// Adds what `wg` reveals.
+    ///
+    /// A status that scoring could never produce, like a letter marked not used ahead of the
+    /// same letter marked misplaced, rules out every word.
+    pub fn add(&mut self, wg: &WordGuessN<N>) {
+        let word = wg.word().0;
+        let status = wg.status.0;
+        let mut seen_unused: Vec<Tile> = vec![];
+        for (position, (&l, s)) in word.iter().zip(status).enumerate() {
+            match s {
+                LetterGuess::Correct => match self.correct[position] {
+                    Some(c) if c != l => self.impossible = true,
+                    _ => self.correct[position] = Some(l),
+                },
+                LetterGuess::Misplaced => {
+                    if seen_unused.contains(&l) {
+                        self.impossible = true;
+                    }
+                    self.exclude(position, l);
+                }
+                LetterGuess::NotUsed => {
+                    seen_unused.push(l);
+                    self.exclude(position, l);
+                }
+            }
+        }
+        for &l in word.iter() {
+            let (used, unused) = word.iter().zip(status).filter(|(&w, _)| w == l).fold(
+                (0, false),
+                |(used, unused), (_, s)| match s {
+                    LetterGuess::NotUsed => (used, true),
+                    _ => (used + 1, unused),
+                },
+            );
+            let i = match self.counts.iter().position(|&(tile, _, _)| tile == l) {
+                Some(i) => i,
+                None => {
+                    self.counts.push((l, 0, N as u8));
+                    self.counts.len() - 1
+                }
+            };
+            let (_, min, max) = &mut self.counts[i];
+            *min = (*min).max(used);
+            if unused {
+                *max = (*max).min(used);
+            }
+            if min > max {
+                self.impossible = true;
+            }
+        }
+    }
+
+    fn exclude(&mut self, position: usize, tile: Tile) {
+        if !self.excluded[position].contains(&tile) {
+            self.excluded[position].push(tile);
+        }
+    }
+
+    /// Whether `word` could still be the target.
+    pub fn matches(&self, word: &WordN<N>) -> bool {
+        if self.impossible {
+            return false;
+        }
+        for ((&l, correct), excluded) in word.0.iter().zip(self.correct).zip(&self.excluded) {
+            if correct.map(|c| c != l).unwrap_or(false) || excluded.contains(&l) {
+                return false;
+            }
+        }
+        self.counts.iter().all(|&(tile, min, max)| {
+            let count = word.0.iter().filter(|&&l| l == tile).count() as u8;
+            min <= count && count <= max
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::knowledge::Knowledge;
+    use crate::words::TARGET_WORDS;
+    use crate::{GuessStatus, Word, WordGuess};
+    use anyhow::Error;
+
+    #[test]
+    fn knowledge_agrees_with_scoring() -> Result<(), Error> {
+        let guesses = ["raise", "geese", "cloth", "mamma", "llama"];
+        for &target in TARGET_WORDS.iter().step_by(37) {
+            let history = guesses
+                .iter()
+                .map(|&g| Ok(WordGuess::guess(Word::try_from(g)?, target)))
+                .collect::<Result<Vec<WordGuess>, Error>>()?;
+            for n in 1..=history.len() {
+                let knowledge = Knowledge::from_history(&history[..n]);
+                for word in TARGET_WORDS.iter() {
+                    let scored = history[..n]
+                        .iter()
+                        .all(|wg| WordGuess::guess_from(wg.word(), word).status == wg.status);
+                    assert_eq!(knowledge.matches(word), scored, "{:?} {:?}", word, history);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn impossible_status_matches_nothing() -> Result<(), Error> {
+        let knowledge = Knowledge::from_history(&[WordGuess::new(
+            Word::try_from("geese")?,
+            GuessStatus::try_from("--+-+")?,
+        )]);
+        assert!(!TARGET_WORDS.iter().any(|w| knowledge.matches(w)));
+        Ok(())
+    }
+}
@@ -1,6 +1,5 @@
 #![deny(
     bad_style,
-    const_err,
     dead_code,
     improper_ctypes,
     missing_debug_implementations,
@@ -9,7 +8,6 @@
     overflowing_literals,
     path_statements,
     patterns_in_fns_without_body,
-    private_in_public,
     unconditional_recursion,
     unreachable_pub,
     unused,
@@ -21,145 +19,395 @@
 )]
 #![deny(unsafe_code)]
 
-use crate::words::{EXTENDED_WORDS, TARGET_WORDS};
 use crate::LetterGuess::NotUsed;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Debug, Display, Formatter, Write};
 use std::iter::{Peekable, Zip};
 use std::mem::swap;
 use thiserror::Error;
 
+pub mod alphabet;
+pub mod analysis;
+pub mod calendar;
+pub mod format;
+pub mod game;
+pub mod hard_mode;
+pub mod knowledge;
+pub mod matrix;
+pub mod share;
+pub mod solver;
+pub mod tree;
+pub mod word_list;
+pub mod word_set;
 pub mod words;
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
-pub struct Word([u8; 5]);
+/// One tile of a word: a letter, or a group of letters like the Welsh `ll` that's played as
+/// one, held as up to four bytes of lowercase UTF-8.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Tile([u8; 4]);
+
+/// A word of `N` tiles.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WordN<const N: usize>([Tile; N]);
+
+/// A five-letter word, as Wordle itself uses.
+pub type Word = WordN<5>;
 
 #[derive(Debug, Error)]
 pub enum WordError {
-    #[error("Words should be ASCII letters only.  Got '{0}' which contains '{1}'")]
+    #[error("Words should only have letters from their alphabet.  Got '{0}' which contains '{1}'")]
     Chars(String, char),
-    #[error("Words have five letters, got a string containing {0} bytes")]
-    Length(usize),
+    #[error("Words have {1} letters, got {0}")]
+    Length(usize, usize),
+    #[error("Tiles are one or more letters taking up to four bytes, got '{0}'")]
+    Tile(String),
+    #[error("Words should read back as the same tiles in their alphabet, got '{0}'")]
+    Spelling(String),
     #[error("Words not in the word list: {0}")]
     NotWord(String),
+    #[error("Status codes are less than 243, got {0}")]
+    Code(u8),
     #[error("Input doesn't look like a Worlde share")]
     NotWordle,
+    #[error("There is no puzzle {0} in the word list")]
+    Puzzle(usize),
+    #[error("There was no puzzle on {0}, the first was on 2021-06-19")]
+    Date(chrono::NaiveDate),
+    #[error("Time zones are `local`, `utc` or an offset like `+05:30`, got '{0}'")]
+    Zone(String),
+    #[error("Share should have {0} rows for its score, got {1}")]
+    Rows(usize, usize),
+    #[error("Unknown format '{0}'")]
+    Format(String),
+    #[error("Word lists are `original` or `nyt`, got '{0}'")]
+    Version(String),
+    #[error("Sets of words can only be combined with sets over the same list")]
+    Lists,
     #[error("Unknown Lua Error")]
     Unknown,
 }
 
-impl TryFrom<&str> for Word {
-    type Error = WordError;
+impl Tile {
+    /// Where a tile has been used up while scoring a guess.
+    const BLANK: Tile = Tile([0; 4]);
 
-    fn try_from(value: &str) -> Result<Word, WordError> {
+    /// Makes a tile from its letters, in either case.
+    pub fn new(letters: &str) -> Result<Tile, WordError> {
+        let letters = letters.to_lowercase();
+        if let Some(x) = letters.chars().find(|x| !x.is_alphabetic()) {
+            return Err(WordError::Chars(letters, x));
+        }
+        if letters.is_empty() || letters.len() > 4 {
+            return Err(WordError::Tile(letters));
+        }
+        let mut bytes = [0; 4];
+        bytes[..letters.len()].copy_from_slice(letters.as_bytes());
+        Ok(Tile(bytes))
+    }
+
+    /// The tile for a lowercase ASCII letter.
+    const fn ascii(letter: u8) -> Tile {
+        Tile([letter, 0, 0, 0])
+    }
+
+    pub fn as_str(&self) -> &str {
+        let len = self.0.iter().position(|&b| b == 0).unwrap_or(4);
+        std::str::from_utf8(&self.0[..len]).unwrap_or_default()
+    }
+}
+
+impl Display for Tile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Debug for Tile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Tile({})", self))
+    }
+}
+
+impl<const N: usize> WordN<N> {
+    /// Checks that `value` is `N` ASCII letters, in any mix of case, without checking the word
+    /// lists.  Other alphabets are read by [`alphabet::Alphabet::word`].
+    pub fn from_letters(value: &str) -> Result<WordN<N>, WordError> {
         for x in value.chars() {
-            if !('a'..='z').contains(&x) {
+            if !x.is_ascii_alphabetic() {
                 return Err(WordError::Chars(value.into(), x));
             }
         }
         let b = value.as_bytes();
-        if b.len() != 5 {
-            return Err(WordError::Length(b.len()));
+        if b.len() != N {
+            return Err(WordError::Length(b.len(), N));
+        }
+        let mut r = [Tile::BLANK; N];
+        for (tile, b) in r.iter_mut().zip(b) {
+            *tile = Tile::ascii(b.to_ascii_lowercase());
         }
-        let mut r: [u8; 5] = [0; 5];
-        r.copy_from_slice(b);
-        let w = Word(r);
-        if !TARGET_WORDS.contains(&w) && !EXTENDED_WORDS.contains(&w) {
+        Ok(WordN(r))
+    }
+
+    pub fn tiles(&self) -> &[Tile; N] {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for Word {
+    type Error = WordError;
+
+    fn try_from(value: &str) -> Result<Word, WordError> {
+        let w = Word::from_letters(value)?;
+        if !words::is_valid_guess(&w) {
             return Err(WordError::NotWord(value.into()));
         }
         Ok(w)
     }
 }
 
-impl Display for Word {
+impl<const N: usize> Display for WordN<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for ch in self.0.iter() {
-            let ch = char::from_u32(*ch as u32).unwrap();
-            f.write_char(ch)?;
-        }
-        Ok(())
+        self.0.iter().try_for_each(|tile| Display::fmt(tile, f))
     }
 }
 
-impl Debug for Word {
+impl<const N: usize> Debug for WordN<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("Word({})", self))
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+impl<const N: usize> Serialize for WordN<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Word {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Word::try_from(s.as_str()).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum LetterGuess {
     Correct,
     Misplaced,
     NotUsed,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-pub struct GuessStatus(pub [LetterGuess; 5]);
+/// How each letter of an `N`-letter guess scored.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GuessStatusN<const N: usize>(pub [LetterGuess; N]);
+
+/// The status of a five-letter guess.
+pub type GuessStatus = GuessStatusN<5>;
+
 // Wordle 232 6/6:black_large_square::large_yellow_square::large_green_square::black_large_square::black_large_square:
 // :black_large_square::black_large_square::black_large_square::black_large_square::large_yellow_square:
 // :large_green_square::large_yellow_square::large_green_square::black_large_square::black_large_square:
 // :large_green_square::black_large_square::large_green_square::large_green_square::large_green_square:
 // :black_large_square::large_yellow_square::black_large_square::large_yellow_square::large_yellow_square:
 // :large_green_square::large_green_square::large_green_square::large_green_square::large_green_square:
-impl TryFrom<&str> for GuessStatus {
+impl<const N: usize> TryFrom<&str> for GuessStatusN<N> {
     type Error = WordError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let value = if value.contains(':') {
             value
-                .replacen(":black_large_square:", "-", 5)
-                .replacen(":large_yellow_square:", "+", 5)
-                .replacen(":large_green_square:", "=", 5)
+                .replacen(":black_large_square:", "-", N)
+                .replacen(":white_large_square:", "-", N)
+                .replacen(":large_yellow_square:", "+", N)
+                .replacen(":large_blue_square:", "+", N)
+                .replacen(":large_green_square:", "=", N)
+                .replacen(":large_orange_square:", "=", N)
+                // Discord's names for the coloured squares.
+                .replacen(":yellow_square:", "+", N)
+                .replacen(":blue_square:", "+", N)
+                .replacen(":green_square:", "=", N)
+                .replacen(":orange_square:", "=", N)
         } else {
             value.to_string()
         };
+        // Some platforms send the squares with an emoji presentation selector.
+        let value = value.replace('\u{fe0f}', "");
         let chars: Vec<char> = value.chars().collect();
         for &x in chars.iter() {
-            if !"=+-🟩🟨⬛".contains(x) {
+            if !"=+-🟩🟨⬛⬜🟧🟦".contains(x) {
                 return Err(WordError::Chars(value, x));
             }
         }
-        if chars.len() != 5 {
-            return Err(WordError::Length(chars.len()));
+        if chars.len() != N {
+            return Err(WordError::Length(chars.len(), N));
         }
-        let mut r: [LetterGuess; 5] = [NotUsed; 5];
-        for (status, symbol) in r.iter_mut().zip(chars.into_iter()) {
+        let mut r: [LetterGuess; N] = [NotUsed; N];
+        for (status, symbol) in r.iter_mut().zip(chars) {
             match symbol {
-                '=' | '🟩' => *status = LetterGuess::Correct,
-                '+' | '🟨' => *status = LetterGuess::Misplaced,
-                '-' | '⬛' => *status = LetterGuess::NotUsed,
+                '=' | '🟩' | '🟧' => *status = LetterGuess::Correct,
+                '+' | '🟨' | '🟦' => *status = LetterGuess::Misplaced,
+                '-' | '⬛' | '⬜' => *status = LetterGuess::NotUsed,
                 x => return Err(WordError::Chars(value, x)),
             }
         }
-        Ok(GuessStatus(r))
+        Ok(GuessStatusN(r))
     }
 }
 
-impl Display for GuessStatus {
+impl<const N: usize> GuessStatusN<N> {
+    /// How many different statuses a guess can get.
+    pub const COUNT: usize = 3usize.pow(N as u32);
+
+    /// Every letter correct.
+    pub const SOLVED: GuessStatusN<N> = GuessStatusN([LetterGuess::Correct; N]);
+
+    /// Numbers the status below [`GuessStatusN::COUNT`], reading it as a base 3 number with
+    /// the first letter least significant, not used as 0, misplaced as 1 and correct as 2.
+    pub fn index(&self) -> usize {
+        self.0.iter().rev().fold(0, |acc, status| {
+            acc * 3
+                + match status {
+                    LetterGuess::NotUsed => 0,
+                    LetterGuess::Misplaced => 1,
+                    LetterGuess::Correct => 2,
+                }
+        })
+    }
+
+    /// Every status, in order of their indices.
+    pub fn all() -> impl Iterator<Item = GuessStatusN<N>> {
+        (0..GuessStatusN::<N>::COUNT).map(GuessStatusN::from_index)
+    }
+
+    fn from_index(mut index: usize) -> GuessStatusN<N> {
+        let mut r = [NotUsed; N];
+        for status in r.iter_mut() {
+            *status = match index % 3 {
+                0 => LetterGuess::NotUsed,
+                1 => LetterGuess::Misplaced,
+                _ => LetterGuess::Correct,
+            };
+            index /= 3;
+        }
+        GuessStatusN(r)
+    }
+}
+
+impl GuessStatus {
+    /// Encodes the status in a byte, which five letters' worth of statuses just fit: see
+    /// [`GuessStatusN::index`].
+    pub fn code(&self) -> u8 {
+        self.index() as u8
+    }
+}
+
+impl From<GuessStatus> for u8 {
+    fn from(status: GuessStatus) -> Self {
+        status.code()
+    }
+}
+
+impl TryFrom<u8> for GuessStatus {
+    type Error = WordError;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        if code as usize >= GuessStatus::COUNT {
+            return Err(WordError::Code(code));
+        }
+        Ok(GuessStatus::from_index(code as usize))
+    }
+}
+
+impl<const N: usize> Display for GuessStatusN<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for status in self.0 {
-            f.write_char(match status {
-                LetterGuess::Correct => '🟩',
-                LetterGuess::Misplaced => '🟨',
-                LetterGuess::NotUsed => '⬛',
-            })?;
+        Display::fmt(&self.in_palette(Palette::default()), f)
+    }
+}
+
+/// The squares a share is drawn with, matching the game's dark, light and high contrast
+/// themes.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Palette {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    HighContrastLight,
+}
+
+impl Palette {
+    pub fn square(self, status: LetterGuess) -> char {
+        let high_contrast = matches!(self, Palette::HighContrast | Palette::HighContrastLight);
+        match status {
+            LetterGuess::Correct if high_contrast => '🟧',
+            LetterGuess::Correct => '🟩',
+            LetterGuess::Misplaced if high_contrast => '🟦',
+            LetterGuess::Misplaced => '🟨',
+            LetterGuess::NotUsed => match self {
+                Palette::Dark | Palette::HighContrast => '⬛',
+                Palette::Light | Palette::HighContrastLight => '⬜',
+            },
+        }
+    }
+}
+
+/// Displays a status, or a [`share::Share`], with the squares from a [`Palette`].
+#[derive(Copy, Clone, Debug)]
+pub struct InPalette<T>(pub T, pub Palette);
+
+impl<const N: usize> GuessStatusN<N> {
+    pub fn in_palette(self, palette: Palette) -> InPalette<GuessStatusN<N>> {
+        InPalette(self, palette)
+    }
+}
+
+impl<const N: usize> Display for InPalette<GuessStatusN<N>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for status in self.0 .0 {
+            f.write_char(self.1.square(status))?;
         }
         Ok(())
     }
 }
 
-impl Debug for GuessStatus {
+impl<const N: usize> Debug for GuessStatusN<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("GuessStatus({})", self))
     }
 }
 
+impl<const N: usize> Serialize for GuessStatusN<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let symbols: String = self
+            .0
+            .iter()
+            .map(|status| match status {
+                LetterGuess::Correct => '=',
+                LetterGuess::Misplaced => '+',
+                LetterGuess::NotUsed => '-',
+            })
+            .collect();
+        serializer.serialize_str(&symbols)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for GuessStatusN<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        GuessStatusN::try_from(s.as_str()).map_err(de::Error::custom)
+    }
+}
+
+/// A guess of `N` letters and the status it got.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub struct WordGuess {
-    word: Word,
-    pub status: GuessStatus,
+pub struct WordGuessN<const N: usize> {
+    word: WordN<N>,
+    pub status: GuessStatusN<N>,
 }
 
+/// A five-letter guess and its status.
+pub type WordGuess = WordGuessN<5>;
+
 fn zip4<A, B, C, D>(a: A, b: B, c: C, d: D) -> Zip<Zip<A, B>, Zip<C, D>>
 where
     A: Iterator,
@@ -170,14 +418,22 @@ where
     Iterator::zip(Iterator::zip(a, b), Iterator::zip(c, d))
 }
 
-impl WordGuess {
-    pub fn guess(guess: Word, target: Word) -> WordGuess {
-        WordGuess::guess_from(guess, &target)
+impl<const N: usize> WordGuessN<N> {
+    pub fn new(word: WordN<N>, status: GuessStatusN<N>) -> WordGuessN<N> {
+        WordGuessN { word, status }
+    }
+
+    pub fn guess(guess: WordN<N>, target: WordN<N>) -> WordGuessN<N> {
+        WordGuessN::guess_from(guess, &target)
+    }
+
+    pub fn word(&self) -> WordN<N> {
+        self.word
     }
 
-    pub fn guess_from(guess: Word, target: &Word) -> WordGuess {
+    pub fn guess_from(guess: WordN<N>, target: &WordN<N>) -> WordGuessN<N> {
         let mut available = target.0;
-        let mut result: [LetterGuess; 5] = [LetterGuess::NotUsed; 5];
+        let mut result: [LetterGuess; N] = [LetterGuess::NotUsed; N];
         for ((t, g), (a, r)) in zip4(
             target.0.iter(),
             guess.0.iter(),
@@ -185,7 +441,7 @@ impl WordGuess {
             result.iter_mut(),
         ) {
             if t == g {
-                *a = 0;
+                *a = Tile::BLANK;
                 *r = LetterGuess::Correct;
             }
         }
@@ -193,33 +449,54 @@ impl WordGuess {
             if *r != LetterGuess::Correct {
                 for a in available.iter_mut() {
                     if *a == g {
-                        *a = 0;
+                        *a = Tile::BLANK;
                         *r = LetterGuess::Misplaced;
                         break;
                     }
                 }
             }
         }
-        WordGuess {
+        WordGuessN {
             word: guess,
-            status: GuessStatus(result),
+            status: GuessStatusN(result),
         }
     }
 }
 
-struct WindowIter<I, F, T> where I: Iterator, F: Fn(&T, &I::Item) -> T {
+/// Chooses the next word to guess, given the feedback from the guesses made so far, for words
+/// of `N` letters.
+///
+/// Implementations live in [`solver`], and any of them can be played with [`solver::play`].
+pub trait Strategy<const N: usize = 5>: Debug + Sync {
+    /// Returns the next word to guess, or `None` if no target is consistent with `history`.
+    fn next_guess(&self, history: &[WordGuessN<N>]) -> Option<WordN<N>>;
+}
+
+#[allow(dead_code)]
+struct WindowIter<I, F, T>
+where
+    I: Iterator,
+    F: Fn(&T, &I::Item) -> T,
+{
     inner: Peekable<I>,
     func: F,
     carry: Option<T>,
     started: bool,
 }
 
-trait WindowMap<I: Iterator, F, T> where  F: Fn(&T, &I::Item) -> T {
+#[allow(dead_code)]
+trait WindowMap<I: Iterator, F, T>
+where
+    F: Fn(&T, &I::Item) -> T,
+{
     fn window(self, initial: T, func: F) -> WindowIter<I, F, T>;
 }
 
-impl <I, F, T> WindowMap<I, F, T> for I
-where I: Iterator, F: Fn(&T, &I::Item) -> T {
+impl<I, F, T> WindowMap<I, F, T> for I
+where
+    I: Iterator,
+    F: Fn(&T, &I::Item) -> T,
+{
     fn window(self, initial: T, func: F) -> WindowIter<I, F, T> {
         WindowIter {
             inner: self.peekable(),
@@ -230,7 +507,11 @@ where I: Iterator, F: Fn(&T, &I::Item) -> T {
     }
 }
 
-impl <I, F, T> Iterator for WindowIter<I, F, T> where I: Iterator, F: Fn(&T, &I::Item) -> T {
+impl<I, F, T> Iterator for WindowIter<I, F, T>
+where
+    I: Iterator,
+    F: Fn(&T, &I::Item) -> T,
+{
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -263,7 +544,11 @@ impl <I, F, T> Iterator for WindowIter<I, F, T> where I: Iterator, F: Fn(&T, &I:
 
 #[cfg(test)]
 mod test {
-    use crate::{LetterGuess, Word, WordGuess, TARGET_WORDS};
+    use crate::words::TARGET_WORDS;
+    use crate::{
+        GuessStatus, GuessStatusN, LetterGuess, Palette, Word, WordError, WordGuess, WordGuessN,
+        WordN,
+    };
     use anyhow::Error;
 
     #[test]
@@ -362,6 +647,74 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn guess_longer_words() -> Result<(), Error> {
+        let guess = WordN::<7>::from_letters("letters")?;
+        let target = WordN::<7>::from_letters("settler")?;
+        let result = WordGuessN::guess(guess, target);
+        assert_eq!(result.status, GuessStatusN::try_from("+===+++")?);
+        assert_eq!(GuessStatusN::<7>::all().count(), 2187);
+        assert!(matches!(
+            WordN::<7>::from_letters("cigar"),
+            Err(WordError::Length(5, 7))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn every_palette_is_parsed() -> Result<(), Error> {
+        let status = GuessStatus::try_from("-+=-=")?;
+        for palette in [
+            Palette::Dark,
+            Palette::Light,
+            Palette::HighContrast,
+            Palette::HighContrastLight,
+        ] {
+            let shown = status.in_palette(palette).to_string();
+            assert_eq!(GuessStatus::try_from(shown.as_str())?, status);
+        }
+        assert_eq!(
+            status.in_palette(Palette::HighContrastLight).to_string(),
+            "⬜🟦🟧⬜🟧"
+        );
+        assert_eq!(
+            GuessStatus::try_from(
+                ":white_large_square::large_blue_square::large_orange_square:\
+                :white_large_square::large_orange_square:"
+            )?,
+            status
+        );
+        assert_eq!(
+            GuessStatus::try_from(
+                ":black_large_square::yellow_square::green_square:\
+                :black_large_square::orange_square:"
+            )?,
+            status
+        );
+        assert_eq!(
+            GuessStatus::try_from(":white_large_square::blue_square::green_square:-=")?,
+            status
+        );
+        assert_eq!(GuessStatus::try_from("⬛\u{fe0f}🟨🟩⬛\u{fe0f}🟩")?, status);
+        Ok(())
+    }
+
+    #[test]
+    fn status_codes_round_trip() -> Result<(), Error> {
+        let all: Vec<GuessStatus> = GuessStatus::all().collect();
+        assert_eq!(all.len(), GuessStatus::COUNT);
+        for (code, status) in all.iter().enumerate() {
+            assert_eq!(status.code() as usize, code);
+            assert_eq!(GuessStatus::try_from(status.code())?, *status);
+        }
+        assert_eq!(GuessStatus::try_from("-----")?.code(), 0);
+        assert_eq!(GuessStatus::try_from("+----")?.code(), 1);
+        assert_eq!(GuessStatus::try_from("-=---")?.code(), 6);
+        assert_eq!(u8::from(GuessStatus::try_from("=====")?), 242);
+        assert!(GuessStatus::try_from(243).is_err());
+        Ok(())
+    }
+
     #[test]
     fn index_of_day_works() {
         assert_eq!(
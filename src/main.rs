@@ -1,180 +1,856 @@
-use std::collections::{BTreeMap, BTreeSet};
+use chrono::NaiveDate;
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
 use std::io;
-use std::io::{BufRead, stdout, Write};
-use std::str::FromStr;
+use std::io::{stdout, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::clap::AppSettings;
 use structopt::StructOpt;
-use wordle::words::{EXTENDED_WORDS, TARGET_WORDS};
-use wordle::{GuessStatus, Word, WordError, WordGuess};
-
-use rayon::prelude::*;
+use wordle::alphabet::Alphabet;
+use wordle::analysis::{Analysis, RowAnalysis};
+use wordle::calendar::{puzzle_for_date, Zone};
+use wordle::format::{paint, Format};
+use wordle::game::Game;
+use wordle::hard_mode;
+use wordle::knowledge::Knowledge;
+use wordle::matrix::FeedbackMatrix;
+use wordle::share::Share;
+use wordle::solver::{
+    benchmark, candidates, rank_guesses, Buckets, Entropy, ExpectedSize, Metric, Minimax, Naive,
+    Ranked,
+};
+use wordle::tree::DecisionTree;
+use wordle::word_list::{WordList, WordListError};
+use wordle::word_set::WordSet;
+use wordle::words::{ListVersion, EXTENDED_WORDS};
+use wordle::{GuessStatus, LetterGuess, Strategy, Word, WordError, WordGuess};
 
 #[derive(Debug, StructOpt)]
 enum Opt {
     FilterFromGuess(FilterFromGuessOpt),
     Analyse(AnalyseOpt),
+    Suggest(SuggestOpt),
+    Benchmark(BenchmarkOpt),
+    Tree(TreeOpt),
+    Solve(SolveOpt),
+    Play(PlayOpt),
+}
+
+// Where the words come from, shared by every subcommand that works from the word lists.  These
+// aren't doc comments, as structopt would take them for the subcommand's description.
+#[derive(Debug, StructOpt)]
+struct ListOptions {
+    #[structopt(short = "x", long)]
+    extend: bool,
+    /// Which built-in targets to use: original or nyt, by default the list in use for the puzzle
+    /// where there is one, or original
+    #[structopt(long, conflicts_with = "target-list")]
+    list: Option<ListVersion>,
+    /// Targets to use instead of the built-in list, as JSON or whitespace-separated text
+    #[structopt(long = "targets")]
+    target_list: Option<PathBuf>,
+    /// More words that can be guessed, as JSON or whitespace-separated text
+    #[structopt(long = "guesses")]
+    guess_list: Option<PathBuf>,
+    /// The tiles the words in those files are spelled with: english, spanish, german, welsh, or
+    /// a list like `a,b,c,ch`
+    #[structopt(long, default_value = "english")]
+    alphabet: Alphabet,
+    /// Look statuses up in a feedback matrix, cached under $XDG_CACHE_HOME
+    #[structopt(long)]
+    matrix: bool,
+    /// How to show statuses: ascii, emoji, shortcodes, ansi or text, with emoji and shortcodes
+    /// taking a palette like `emoji-light` or `shortcodes-high-contrast`
+    #[structopt(short, long)]
+    format: Option<Format>,
+}
+
+// How guesses are picked, shared by every subcommand that makes them.
+#[derive(Debug, StructOpt)]
+struct StrategyOptions {
+    /// Which strategy picks the guesses: naive, entropy, minimax, expected or buckets
+    #[structopt(short, long, default_value = "entropy", possible_values = STRATEGIES)]
+    strategy: String,
+    /// Only make guesses that keep to the hard mode rules
+    #[structopt(long)]
+    hard: bool,
 }
 
 #[derive(Debug, StructOpt)]
 struct FilterFromGuessOpt {
     word: String,
     guess: String,
-    #[structopt(short = "x", long)]
-    extend: bool,
+    #[structopt(flatten)]
+    lists: ListOptions,
 }
 
 #[derive(Debug, StructOpt)]
 struct AnalyseOpt {
-    #[structopt(short = "x", long)]
-    extend: bool,
+    /// The answer to the shared puzzle, rather than looking it up by puzzle number
+    #[structopt(short, long)]
+    target: Option<String>,
+    /// The day the shared puzzle was set, like 2022-02-05, to check against its puzzle number
+    #[structopt(short, long)]
+    date: Option<NaiveDate>,
+    #[structopt(flatten)]
+    lists: ListOptions,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(setting = AppSettings::AllowLeadingHyphen)]
+struct SuggestOpt {
+    /// Pairs of guessed word and the status it got, e.g. `cigar -+--=`
+    guesses: Vec<String>,
+    #[structopt(short = "n", long, default_value = "10")]
+    count: usize,
+    /// How to rank guesses: entropy, minimax, expected or buckets
+    #[structopt(short, long, default_value = "entropy", parse(try_from_str = parse_metric))]
+    metric: Box<dyn Metric>,
+    #[structopt(flatten)]
+    solver: StrategyOptions,
+    #[structopt(flatten)]
+    lists: ListOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct SolveOpt {
+    #[structopt(short = "n", long, default_value = "5")]
+    count: usize,
+    /// How to rank guesses: entropy, minimax, expected or buckets
+    #[structopt(short, long, default_value = "entropy", parse(try_from_str = parse_metric))]
+    metric: Box<dyn Metric>,
+    #[structopt(flatten)]
+    solver: StrategyOptions,
+    /// The first guess to suggest, instead of the strategy's choice
+    #[structopt(short, long)]
+    opener: Option<String>,
+    /// The day of the puzzle being solved, like 2022-02-05, to number the share
+    #[structopt(short, long)]
+    date: Option<NaiveDate>,
+    /// The time zone that decides what day it is: local, utc, or an offset like +05:30
+    #[structopt(short = "z", long, default_value = "local")]
+    timezone: Zone,
+    #[structopt(flatten)]
+    lists: ListOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct PlayOpt {
+    /// Play this puzzle rather than today's
+    #[structopt(short, long, conflicts_with_all = &["date", "random"])]
+    puzzle: Option<usize>,
+    /// Play the puzzle for this day, like 2022-02-05, rather than today's
+    #[structopt(short, long, conflicts_with = "random")]
+    date: Option<NaiveDate>,
+    /// Play a random puzzle rather than today's
+    #[structopt(short, long)]
+    random: bool,
+    /// The time zone that decides what day it is: local, utc, or an offset like +05:30
+    #[structopt(short = "z", long, default_value = "local")]
+    timezone: Zone,
+    /// Hints that have been revealed must be used in later guesses
+    #[structopt(long)]
+    hard: bool,
+    #[structopt(flatten)]
+    lists: ListOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct BenchmarkOpt {
+    #[structopt(flatten)]
+    solver: StrategyOptions,
+    /// The first guess of every game, instead of the strategy's choice
+    #[structopt(short, long)]
+    opener: Option<String>,
+    /// Give up on a target after this many guesses
+    #[structopt(short, long, default_value = "12")]
+    limit: usize,
+    /// How many of the hardest targets to list
+    #[structopt(short, long, default_value = "10")]
+    worst: usize,
+    /// Play a decision tree saved by the `tree` subcommand, instead of a strategy
+    #[structopt(short, long, conflicts_with_all = &["strategy", "opener"])]
+    tree: Option<PathBuf>,
+    #[structopt(flatten)]
+    lists: ListOptions,
+}
+
+#[derive(Debug, StructOpt)]
+struct TreeOpt {
+    #[structopt(flatten)]
+    solver: StrategyOptions,
+    /// The first guess, instead of the strategy's choice
+    #[structopt(short, long)]
+    opener: Option<String>,
+    /// Write the tree as JSON rather than text
+    #[structopt(short, long)]
+    json: bool,
+    /// Where to write the tree, rather than standard output
+    #[structopt(short = "O", long)]
+    output: Option<PathBuf>,
+    #[structopt(flatten)]
+    lists: ListOptions,
+}
+
+const STRATEGIES: &[&str] = &["naive", "entropy", "minimax", "expected", "buckets"];
+
+impl StrategyOptions {
+    fn build(
+        &self,
+        targets: &[Word],
+        guesses: &[Word],
+        matrix: &Option<Arc<FeedbackMatrix>>,
+    ) -> Box<dyn Strategy> {
+        let hard = self.hard;
+        match self.strategy.as_str() {
+            "naive" => Box::new(Naive::new(targets)),
+            "minimax" => ranked(Minimax, targets, guesses, hard, matrix),
+            "expected" => ranked(ExpectedSize, targets, guesses, hard, matrix),
+            "buckets" => ranked(Buckets, targets, guesses, hard, matrix),
+            _ => ranked(Entropy, targets, guesses, hard, matrix),
+        }
+    }
+}
+
+fn ranked<M: Metric + 'static>(
+    metric: M,
+    targets: &[Word],
+    guesses: &[Word],
+    hard: bool,
+    matrix: &Option<Arc<FeedbackMatrix>>,
+) -> Box<dyn Strategy> {
+    let ranked = Ranked::new(metric, targets, guesses).with_hard_mode(hard);
+    match matrix {
+        Some(matrix) => Box::new(ranked.with_matrix(matrix.clone())),
+        None => Box::new(ranked),
+    }
+}
+
+impl ListOptions {
+    /// The targets, and every word that can be guessed including the targets, from the given
+    /// files or the built-in lists, using `version` unless another was asked for.
+    ///
+    /// Both lists read words in the alphabet asked for, even when they're built in.
+    fn load(&self, version: ListVersion) -> Result<(WordList, WordList), WordListError> {
+        let targets = match &self.target_list {
+            Some(path) => WordList::load_in(path, &self.alphabet)?,
+            None => WordList::version(self.list.unwrap_or(version)).spelled_in(&self.alphabet)?,
+        };
+        let guesses = match &self.guess_list {
+            Some(path) => targets.union(WordList::load_in(path, &self.alphabet)?.words()),
+            None if self.extend => targets.union(&EXTENDED_WORDS),
+            None => targets.clone(),
+        };
+        Ok((targets, guesses.spelled_in(&self.alphabet)?))
+    }
+
+    /// Every word that can be typed in as a guess: the `guesses` and every built-in word, as
+    /// any word at all might have been played, whatever the guesses are ranked from.
+    fn typed(&self, guesses: &WordList) -> WordList {
+        guesses.union(WordList::embedded().words())
+    }
+
+    /// Loads the feedback matrix for every word against the targets if it was asked for,
+    /// building it if it isn't in the cache.
+    fn matrix(
+        &self,
+        targets: &WordList,
+        guesses: &WordList,
+    ) -> io::Result<Option<Arc<FeedbackMatrix>>> {
+        if !self.matrix {
+            return Ok(None);
+        }
+        let all = targets
+            .union(WordList::embedded().words())
+            .union(guesses.words());
+        let path = FeedbackMatrix::default_cache_path(all.words(), targets.words());
+        let matrix = FeedbackMatrix::cached(&path, all.words(), targets.words())?;
+        Ok(Some(Arc::new(matrix)))
+    }
+}
+
+fn parse_metric(name: &str) -> Result<Box<dyn Metric>, String> {
+    match name {
+        "entropy" => Ok(Box::new(Entropy)),
+        "minimax" => Ok(Box::new(Minimax)),
+        "expected" => Ok(Box::new(ExpectedSize)),
+        "buckets" => Ok(Box::new(Buckets)),
+        _ => Err(format!("Unknown metric '{}'", name)),
+    }
+}
+
+fn opener(
+    opener: Option<String>,
+    strategy: &dyn Strategy,
+    guesses: &WordList,
+) -> Result<Word, Box<dyn Error>> {
+    match opener {
+        Some(opener) => Ok(guesses.word(&opener)?),
+        None => Ok(strategy
+            .next_guess(&[])
+            .ok_or("The strategy has no first guess")?),
+    }
+}
+
+/// The puzzle `share` is for, which has to be the one set on `date` if that's given too.
+fn shared_puzzle(share: &Share, date: Option<NaiveDate>) -> Result<usize, Box<dyn Error>> {
+    if let Some(date) = date {
+        let puzzle = puzzle_for_date(date)?;
+        if puzzle != share.puzzle {
+            return Err(format!(
+                "The share is for puzzle {}, but {} was puzzle {}",
+                share.puzzle, date, puzzle
+            )
+            .into());
+        }
+    }
+    Ok(share.puzzle)
+}
+
+fn allowed(guesses: &[Word], history: &[WordGuess], hard: bool) -> Vec<Word> {
+    guesses
+        .iter()
+        .copied()
+        .filter(|&guess| !hard || hard_mode::check(history, guess).is_ok())
+        .collect()
+}
+
+fn print_ranking(
+    guesses: &[Word],
+    remaining: &[Word],
+    metric: &dyn Metric,
+    count: usize,
+    matrix: Option<&FeedbackMatrix>,
+) {
+    matrix
+        .and_then(|matrix| matrix.rank_guesses(guesses, remaining, metric))
+        .unwrap_or_else(|| rank_guesses(guesses, remaining, metric))
+        .iter()
+        .take(count)
+        .for_each(|g| {
+            println!(
+                "{:?} {}{}",
+                g.guess,
+                metric.describe(g.score),
+                if g.candidate {
+                    ""
+                } else {
+                    " (not a candidate)"
+                }
+            )
+        });
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let opt = Opt::from_args();
     match opt {
         Opt::FilterFromGuess(opt) => {
-            let word = Word::try_from(opt.word.as_str())?;
+            let (targets, guesses) = opt.lists.load(ListVersion::default())?;
+            let word = opt.lists.typed(&guesses).word(&opt.word)?;
             let guess = GuessStatus::try_from(opt.guess.as_str())?;
-            let iter = TARGET_WORDS.into_iter();
-            let mut results: Vec<Word> = if opt.extend {
-                iter.chain(EXTENDED_WORDS.into_iter())
-                    .filter(|target| {
-                        let wg = WordGuess::guess(word, *target);
-                        wg.status == guess
-                    })
-                    .collect()
+            let wg = WordGuess::new(word, guess);
+            if let Some(format) = opt.lists.format {
+                println!("{}", wg.in_format(format));
+            }
+            let words = Arc::new(if opt.lists.extend {
+                guesses.clone()
             } else {
-                iter.filter(|target| {
-                    let wg = WordGuess::guess(word, *target);
-                    wg.status == guess
-                })
-                .collect()
+                targets
+            });
+            let matrix = opt.lists.matrix(&words, &guesses)?;
+            let mut results: Vec<Word> = match matrix.and_then(|m| m.matching(word, guess)) {
+                Some(results) => results,
+                None => {
+                    let knowledge = Knowledge::from_history(&[wg]);
+                    WordSet::consistent(&WordSet::full(&words), &knowledge)
+                        .iter()
+                        .collect()
+                }
             };
             results.sort();
             results.iter().for_each(|w| println!("{:?}", w));
         }
         Opt::Analyse(opt) => {
-            let stdin = io::stdin();
-            let mut lines = stdin.lock().lines();
-            let first = lines.next().ok_or(WordError::NotWordle)??;
-            let mut parse = first.split(' ');
-            let wordle = parse.next().ok_or(WordError::NotWordle)?;
-            if wordle != "Wordle" {
-                return Err(WordError::NotWordle.into());
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            let share: Share = text.parse()?;
+            let puzzle = shared_puzzle(&share, opt.date)?;
+            let (targets, guesses) = opt.lists.load(ListVersion::for_puzzle(puzzle))?;
+            // Without a list of guesses, any word at all might have been played.
+            let all = Arc::new(match opt.lists.guess_list {
+                Some(_) => guesses.clone(),
+                None => opt.lists.typed(&guesses),
+            });
+            let target = match opt.target {
+                Some(target) => all.word(&target)?,
+                None => targets.puzzle(puzzle)?,
+            };
+            let pool = if opt.lists.extend { &guesses } else { &targets };
+            let candidates = WordSet::full(&all).filter(|w| pool.contains(w));
+
+            let mut analysis = Analysis::new(target, WordSet::full(&all), candidates, &share.rows);
+            if let Some(matrix) = opt.lists.matrix(pool, &all)? {
+                analysis = analysis.with_matrix(matrix);
             }
-            let puzzle_number = parse.next().ok_or(WordError::NotWordle)?;
-            let puzzle_number = usize::from_str(puzzle_number)?;
-            let target = TARGET_WORDS[puzzle_number];
-
-            let maybe_first_guess: Vec<Result<GuessStatus, anyhow::Error>> = parse
-                .next()
-                .map(|rest| {
-                    if rest.contains(':') {
-                        let g = GuessStatus::try_from(rest.trim_start_matches(|char| char != ':'));
-                        match g {
-                            Ok(g) => vec![Ok(g)],
-                            _ => vec![],
-                        }
-                    } else {
-                        vec![]
-                    }
+            let rows = analysis
+                .map(|row| {
+                    print!(".");
+                    stdout().flush()?;
+                    Ok(row)
                 })
-                .unwrap_or_else(std::vec::Vec::new);
+                .collect::<Result<Vec<RowAnalysis>, io::Error>>();
 
-            let guesses = lines
-                .filter(|line| line.as_ref().map(|l| !l.is_empty()).unwrap_or(true))
-                .map(|line| {
-                    line.map(|line| GuessStatus::try_from(line.as_str()))?
-                        .map_err(Into::into) as anyhow::Result<GuessStatus>
-                });
-            let mut guesses = maybe_first_guess.into_iter().chain(guesses);
+            println!();
+            let format = opt.lists.format.unwrap_or_default();
+            for row in rows? {
+                let possible = row.possible_guesses.len();
+                match (&row.fewest, &row.most) {
+                    (Some(fewest), Some(most)) => println!(
+                        "Guess resulting in {} has {} possible guess{} for between {} and {} targets left, guessing {:?} and {:?} respectively.",
+                        row.status.in_format(format),
+                        possible,
+                        if possible != 1 { "es" } else { "" },
+                        fewest.remaining,
+                        most.remaining,
+                        fewest.guesses,
+                        most.guesses,
+                    ),
+                    _ => println!(
+                        "Guess resulting in {} has no possible chain of guesses.",
+                        row.status.in_format(format)
+                    ),
+                }
+            }
+        }
+        Opt::Benchmark(opt) => {
+            let (targets, guesses) = opt.lists.load(ListVersion::default())?;
+            let matrix = opt.lists.matrix(&targets, &guesses)?;
+            // The tree might have been built with guesses from beyond these lists.
+            let typed = opt.lists.typed(&guesses);
+            let strategy: Box<dyn Strategy> = match &opt.tree {
+                Some(path) => {
+                    let file = BufReader::new(File::open(path)?);
+                    Box::new(DecisionTree::from_json(file, &typed)?)
+                }
+                None => opt.solver.build(targets.words(), guesses.words(), &matrix),
+            };
+            let opener = opener(opt.opener, strategy.as_ref(), &typed)?;
+            let games = benchmark(strategy.as_ref(), Some(opener), targets.words(), opt.limit);
 
-            let all_words: BTreeSet<Word> = TARGET_WORDS
+            let mut results: Vec<(Word, usize, bool, &[WordGuess])> = targets
+                .words()
                 .iter()
-                .chain(EXTENDED_WORDS.iter())
-                .copied()
+                .zip(games.iter())
+                .map(|(&target, game)| {
+                    let solved = game.last().map(|wg| wg.word()) == Some(target);
+                    (target, game.len(), solved, game.as_slice())
+                })
+                .collect();
+            let solved: Vec<usize> = results
+                .iter()
+                .filter(|(_, _, solved, _)| *solved)
+                .map(|(_, len, _, _)| *len)
                 .collect();
+            let distribution: BTreeMap<usize, usize> =
+                solved.iter().fold(BTreeMap::new(), |mut acc, &len| {
+                    *acc.entry(len).or_insert(0) += 1;
+                    acc
+                });
 
-            struct RowAnalysis {
-                guess: GuessStatus,
-                possible_guesses: BTreeSet<Word>,
-                possible_targets: BTreeMap<Vec<Word>, BTreeSet<Word>>,
+            match &opt.tree {
+                Some(path) => println!(
+                    "Played {} games with the tree in {}, opening with {:?}",
+                    results.len(),
+                    path.display(),
+                    opener
+                ),
+                None => println!(
+                    "Played {} games with the {} strategy, opening with {:?}",
+                    results.len(),
+                    opt.solver.strategy,
+                    opener
+                ),
             }
+            distribution
+                .iter()
+                .for_each(|(len, count)| println!("{:>2}: {}", len, count));
+            if solved.is_empty() {
+                println!("No games solved, so no mean");
+            } else {
+                println!(
+                    "Mean of {:.4} guesses over {} solved games",
+                    solved.iter().sum::<usize>() as f64 / solved.len() as f64,
+                    solved.len()
+                );
+            }
+            println!(
+                "{} took more than six guesses, {} unsolved after {}",
+                solved.iter().filter(|&&len| len > 6).count(),
+                results.len() - solved.len(),
+                opt.limit
+            );
 
-            let initial_guess_chain = BTreeMap::from([(
-                vec![],
-                if opt.extend {
-                    BTreeSet::from_iter(all_words.iter().copied())
-                } else {
-                    BTreeSet::from_iter(TARGET_WORDS.iter().copied())
-                },
-            )]);
-
-            let possible_words = guesses.try_fold(vec![], |mut acc: Vec<RowAnalysis>, guess| {
-                let guess = guess?;
-                let possible_guesses: BTreeSet<Word> = all_words
-                    .iter()
-                    .filter(|&w| WordGuess::guess(*w, target).status == guess)
-                    .copied()
-                    .collect();
-
-                let guess_chains: &BTreeMap<Vec<Word>, BTreeSet<Word>> = acc
-                    .last()
-                    .map(|r| &r.possible_targets)
-                    .unwrap_or(&initial_guess_chain);
-
-                let possible_targets: BTreeMap<Vec<Word>, BTreeSet<Word>> = guess_chains
-                    .par_iter()
-                    .flat_map_iter(|(chain, words)| {
-                        possible_guesses.iter().map(move |&word| {
-                            let mut new_chain = chain.clone();
-                            new_chain.push(word);
-                            let new_set: BTreeSet<Word> = words
-                                .iter()
-                                .filter(|&target| {
-                                    let wg = WordGuess::guess_from(word, target);
-                                    wg.status == guess
-                                })
-                                .copied()
-                                .collect();
-                            (new_chain, new_set)
-                        })
-                    })
-                    .collect();
-
-                print!(".");
-                stdout().flush()?;
-                acc.push(RowAnalysis {
-                    guess,
-                    possible_guesses,
-                    possible_targets,
+            results
+                .sort_by_key(|&(target, len, solved, _)| (solved, std::cmp::Reverse(len), target));
+            println!("Hardest targets:");
+            results
+                .iter()
+                .take(opt.worst)
+                .for_each(|(target, len, solved, game)| {
+                    println!(
+                        "{:?} {} guess{}{}",
+                        target,
+                        len,
+                        if *len != 1 { "es" } else { "" },
+                        if *solved { "" } else { " without solving" }
+                    );
+                    if let Some(format) = opt.lists.format {
+                        game.iter()
+                            .for_each(|wg| println!("  {}", wg.in_format(format)));
+                    }
                 });
-                Ok(acc) as Result<Vec<RowAnalysis>, anyhow::Error>
+        }
+        Opt::Tree(opt) => {
+            let (targets, guesses) = opt.lists.load(ListVersion::default())?;
+            let matrix = opt.lists.matrix(&targets, &guesses)?;
+            let strategy = opt.solver.build(targets.words(), guesses.words(), &matrix);
+            let opener = opener(opt.opener, strategy.as_ref(), &opt.lists.typed(&guesses))?;
+            let tree = DecisionTree::build(strategy.as_ref(), opener, targets.words());
+            let mut out: Box<dyn Write> = match &opt.output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+                None => Box::new(stdout()),
+            };
+            if opt.json {
+                serde_json::to_writer(&mut out, &tree)?;
+                writeln!(out)?;
+            } else {
+                write!(
+                    out,
+                    "{}",
+                    tree.in_format(opt.lists.format.unwrap_or_default())
+                )?;
+            }
+            out.flush()?;
+            if opt.output.is_some() {
+                println!("Solves every target within {} guesses", tree.depth());
+            }
+        }
+        Opt::Suggest(opt) => {
+            let pairs = opt.guesses.chunks_exact(2);
+            if !pairs.remainder().is_empty() {
+                return Err("Guesses should be pairs of a word and its status".into());
+            }
+            let (targets, guesses) = opt.lists.load(ListVersion::default())?;
+            let typed = opt.lists.typed(&guesses);
+            let history = pairs
+                .map(|pair| {
+                    Ok(WordGuess::new(
+                        typed.word(&pair[0])?,
+                        GuessStatus::try_from(pair[1].as_str())?,
+                    ))
+                })
+                .collect::<Result<Vec<WordGuess>, WordError>>()?;
+            if let Some(format) = opt.lists.format {
+                history
+                    .iter()
+                    .for_each(|wg| println!("{}", wg.in_format(format)));
+            }
+            let matrix = opt.lists.matrix(&targets, &guesses)?;
+            let remaining = candidates(targets.words(), &history);
+            println!("{} possible targets left", remaining.len());
+            let strategy = opt.solver.build(targets.words(), guesses.words(), &matrix);
+            match strategy.next_guess(&history) {
+                Some(guess) => println!("Suggested guess: {:?}", guess),
+                None => return Err("No targets match those guesses".into()),
+            }
+            print_ranking(
+                &allowed(guesses.words(), &history, opt.solver.hard),
+                &remaining,
+                opt.metric.as_ref(),
+                opt.count,
+                matrix.as_deref(),
+            );
+        }
+        Opt::Play(opt) => {
+            let puzzle = match (opt.puzzle, opt.date) {
+                (Some(puzzle), _) => Some(puzzle),
+                _ if opt.random => None,
+                (None, date) => Some(puzzle_for_date(
+                    date.unwrap_or_else(|| opt.timezone.today()),
+                )?),
+            };
+            let version = puzzle.map_or(ListVersion::Nyt, ListVersion::for_puzzle);
+            let (targets, guesses) = opt.lists.load(version)?;
+            if targets.is_empty() {
+                return Err("There are no targets to play".into());
+            }
+            let puzzle = puzzle.unwrap_or_else(|| {
+                RandomState::new().build_hasher().finish() as usize % targets.len()
             });
+            let words = Arc::new(opt.lists.typed(&guesses));
+            let mut game = Game::with_words(targets.puzzle(puzzle)?, words, 6, opt.hard);
+            let mut keyboard: BTreeMap<String, LetterGuess> = BTreeMap::new();
 
-            println!();
-            possible_words?
-                .iter()
-                .for_each(|row| {
-                    let guess = &row.guess;
-                    let possible = &row.possible_guesses;
-                    let targets = &row.possible_targets;
-                    let minimum = targets.iter().min_by_key(|(_, a)|{a.len()});
-                    let maximum = targets.iter().max_by_key(|(_, a)|{a.len()});
-                    let (min_path, min_words) = minimum.unwrap();
-                    let (max_path, max_words) = maximum.unwrap();
+            let stdin = io::stdin();
+            let mut lines = stdin.lock().lines();
+            while !game.is_over() {
+                print!("Guess {}> ", game.guesses().len() + 1);
+                stdout().flush()?;
+                let line = match lines.next() {
+                    Some(line) => line?,
+                    // An unfinished game has nothing to share.
+                    None => {
+                        println!();
+                        return Ok(());
+                    }
+                };
+                let wg = match game.submit(line.trim()) {
+                    Ok(wg) => wg,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue;
+                    }
+                };
+                for (tile, status) in wg.word().tiles().iter().zip(wg.status.0) {
+                    let key = keyboard.entry(tile.to_string()).or_insert(status);
+                    *key = status.min(*key);
+                }
+
+                println!();
+                for wg in game.guesses() {
                     println!(
-                        "Guess resulting in {} has {} possible guess{} for between {} and {} targets left, guessing {:?} and {:?} respectively.",
-                        guess,
-                        possible.len(),
-                        if possible.len() != 1 { "es" } else { "" },
-                        min_words.len(),
-                        max_words.len(),
-                        min_path,
-                        max_path,
-                    )
-                })
+                        "  {}",
+                        wg.in_format(opt.lists.format.unwrap_or(Format::Ansi))
+                    );
+                }
+                println!();
+                for (indent, keys) in ["qwertyuiop", "asdfghjkl", "zxcvbnm"].iter().enumerate() {
+                    let row: String = keys
+                        .chars()
+                        .map(|key| key.to_string())
+                        .map(|key| paint(&key, keyboard.get(&key).copied()))
+                        .collect();
+                    println!("{:indent$}{}", "", row, indent = indent * 2);
+                }
+                println!();
+            }
+
+            if !game.is_won() {
+                println!("The word was {}", game.target());
+            }
+            let share = Share::from_game(puzzle, &game);
+            println!("{}", share.in_format(opt.lists.format.unwrap_or_default()));
+        }
+        Opt::Solve(opt) => {
+            let puzzle = puzzle_for_date(opt.date.unwrap_or_else(|| opt.timezone.today()))?;
+            let (targets, guesses) = opt.lists.load(ListVersion::for_puzzle(puzzle))?;
+            let matrix = opt.lists.matrix(&targets, &guesses)?;
+            let strategy = opt.solver.build(targets.words(), guesses.words(), &matrix);
+            let typed = opt.lists.typed(&guesses);
+            let opener = opener(opt.opener, strategy.as_ref(), &typed)?;
+            let mut history: Vec<WordGuess> = vec![];
+            let mut guess = Some(opener);
+            println!("Enter the status each guess gets, e.g. `-+--=`.");
+            println!("Enter a word to guess it instead, `undo` to take back a guess, or `quit`.");
+
+            let stdin = io::stdin();
+            let mut lines = stdin.lock().lines();
+            loop {
+                match guess {
+                    Some(guess) => print!("Guess {:?}> ", guess),
+                    None => print!("No targets left, try `undo`> "),
+                }
+                stdout().flush()?;
+                let line = match lines.next() {
+                    Some(line) => line?,
+                    None => {
+                        println!();
+                        break;
+                    }
+                };
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                let parsed = match tokens.as_slice() {
+                    [] => continue,
+                    ["quit"] | ["q"] => break,
+                    ["undo"] | ["u"] => {
+                        if history.pop().is_none() {
+                            println!("Nothing to undo");
+                        }
+                        Ok((None, None))
+                    }
+                    [token] => match GuessStatus::try_from(*token) {
+                        Ok(status) => Ok((guess, Some(status))),
+                        Err(_) => typed.word(token).map(|word| (Some(word), None)),
+                    },
+                    [word, status] => typed
+                        .word(word)
+                        .and_then(|word| Ok((Some(word), Some(GuessStatus::try_from(*status)?)))),
+                    _ => {
+                        println!("Expected a status, a word, or a word and its status");
+                        continue;
+                    }
+                };
+                let (word, status) = match parsed {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        println!("{}", e);
+                        continue;
+                    }
+                };
+                if let Some(word) = word.filter(|_| opt.solver.hard) {
+                    if let Err(e) = hard_mode::check(&history, word) {
+                        println!("{}", e);
+                        continue;
+                    }
+                }
+                match (word, status) {
+                    (Some(word), None) => {
+                        guess = Some(word);
+                        continue;
+                    }
+                    (Some(word), Some(status)) => {
+                        history.push(WordGuess::new(word, status));
+                        if let Some(format) = opt.lists.format {
+                            history
+                                .iter()
+                                .for_each(|wg| println!("  {}", wg.in_format(format)));
+                        }
+                        if status == GuessStatus::SOLVED {
+                            println!("Solved in {}", history.len());
+                            let share = Share {
+                                puzzle,
+                                score: Some(history.len()),
+                                max_guesses: 6.max(history.len()),
+                                hard_mode: opt.solver.hard,
+                                rows: history.iter().map(|wg| wg.status).collect(),
+                            };
+                            println!();
+                            println!("{}", share.in_format(opt.lists.format.unwrap_or_default()));
+                            break;
+                        }
+                    }
+                    (None, Some(_)) => {
+                        println!("Enter a word to guess first");
+                        continue;
+                    }
+                    (None, None) => {}
+                }
+
+                let remaining = candidates(targets.words(), &history);
+                println!(
+                    "{} possible target{} left",
+                    remaining.len(),
+                    if remaining.len() != 1 { "s" } else { "" }
+                );
+                guess = if history.is_empty() {
+                    Some(opener)
+                } else {
+                    strategy.next_guess(&history)
+                };
+                if remaining.len() > 1 {
+                    print_ranking(
+                        &allowed(guesses.words(), &history, opt.solver.hard),
+                        &remaining,
+                        opt.metric.as_ref(),
+                        opt.count,
+                        matrix.as_deref(),
+                    );
+                }
+            }
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{shared_puzzle, Opt};
+    use anyhow::Error;
+    use chrono::NaiveDate;
+    use structopt::StructOpt;
+    use wordle::calendar::puzzle_for_date;
+    use wordle::share::Share;
+    use wordle::words::ListVersion;
+
+    #[test]
+    fn guesses_are_read_in_the_alphabet_asked_for() -> Result<(), Error> {
+        let path = std::env::temp_dir().join(format!("wordle-guesses-{}.txt", std::process::id()));
+        std::fs::write(&path, "añejo señal")?;
+        let path = path.to_string_lossy();
+        let commands = [
+            vec![
+                "suggest",
+                "--guesses",
+                &path,
+                "--alphabet",
+                "spanish",
+                "añejo",
+                "-----",
+            ],
+            vec![
+                "filter-from-guess",
+                "--guesses",
+                &path,
+                "--alphabet",
+                "spanish",
+                "-x",
+                "--",
+                "señal",
+                "=====",
+            ],
+        ];
+        for command in commands {
+            let opt = Opt::from_iter_safe(std::iter::once("wordle").chain(command))?;
+            let lists = match opt {
+                Opt::Suggest(opt) => opt.lists,
+                Opt::FilterFromGuess(opt) => opt.lists,
+                _ => unreachable!(),
+            };
+            let (targets, guesses) = lists.load(ListVersion::default())?;
+            assert_eq!(guesses.word("SEÑAL")?.to_string(), "señal");
+            assert_eq!(guesses.word("cigar")?, targets.word("cigar")?);
+        }
+        std::fs::remove_file(path.as_ref())?;
+        Ok(())
+    }
+
+    #[test]
+    fn dates_have_to_agree_with_shares() -> Result<(), Error> {
+        let share: Share = "Wordle 227 3/6\n\n⬛⬛⬛⬛⬛\n⬛🟨⬛⬛⬛\n🟩🟩🟩🟩🟩".parse()?;
+        let date = NaiveDate::from_ymd_opt(2022, 2, 1).unwrap();
+        assert_eq!(puzzle_for_date(date)?, 227);
+        assert_eq!(shared_puzzle(&share, None).ok(), Some(227));
+        assert_eq!(shared_puzzle(&share, Some(date)).ok(), Some(227));
+        assert!(shared_puzzle(&share, date.pred_opt()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn any_built_in_word_can_be_typed() -> Result<(), Error> {
+        let commands = [
+            vec!["filter-from-guess", "aahed", "====="],
+            vec!["suggest", "aahed", "====="],
+            vec!["benchmark", "-o", "soare"],
+        ];
+        for command in commands {
+            let opt = Opt::from_iter_safe(std::iter::once("wordle").chain(command))?;
+            let lists = match opt {
+                Opt::FilterFromGuess(opt) => opt.lists,
+                Opt::Suggest(opt) => opt.lists,
+                Opt::Benchmark(opt) => opt.lists,
+                _ => unreachable!(),
+            };
+            let (_, guesses) = lists.load(ListVersion::default())?;
+            assert!(guesses.word("aahed").is_err());
+            let typed = lists.typed(&guesses);
+            assert_eq!(typed.word("aahed")?.to_string(), "aahed");
+            assert_eq!(typed.word("soare")?.to_string(), "soare");
+            assert_eq!(&typed.words()[..guesses.len()], guesses.words());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+use crate::solver::{compare, sizes, Metric, ScoredGuess};
+use crate::{GuessStatusN, WordGuessN, WordN};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"WORDLEFB";
+
+/// The status of every guess against every target, worked out in advance.
+///
+/// Each cell is a single byte encoding the [`GuessStatusN`], so the full set of guesses
+/// against the targets takes about 30MB, and words can have at most five letters.  Building
+/// it takes a while, so it can be saved to and loaded from a cache file, which records which
+/// word lists it was built from.
+#[derive(Clone)]
+pub struct FeedbackMatrix<const N: usize = 5> {
+    guesses: Vec<WordN<N>>,
+    targets: Vec<WordN<N>>,
+    guess_index: HashMap<WordN<N>, usize>,
+    target_index: HashMap<WordN<N>, usize>,
+    cells: Vec<u8>,
+}
+
+impl<const N: usize> Debug for FeedbackMatrix<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "FeedbackMatrix({} guesses, {} targets)",
+            self.guesses.len(),
+            self.targets.len()
+        ))
+    }
+}
+
+/// A 64-bit FNV-1a hash of the word lists, to tell whether a cache file is stale.
+fn fingerprint<const N: usize>(guesses: &[WordN<N>], targets: &[WordN<N>]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for list in [guesses, targets] {
+        let bytes = (list.len() as u32).to_le_bytes().into_iter().chain(
+            list.iter()
+                .flat_map(|w| w.0.iter().flat_map(|t| t.as_str().bytes())),
+        );
+        for b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+fn index<const N: usize>(words: &[WordN<N>]) -> HashMap<WordN<N>, usize> {
+    words.iter().enumerate().map(|(i, &w)| (w, i)).collect()
+}
+
+impl<const N: usize> FeedbackMatrix<N> {
+    /// Where to keep the cache file for these word lists by default: under `$XDG_CACHE_HOME`,
+    /// or `~/.cache`, named for the lists so that each has its own.
+    pub fn default_cache_path(guesses: &[WordN<N>], targets: &[WordN<N>]) -> PathBuf {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("wordle")
+            .join(format!(
+                "feedback-{:016x}.bin",
+                fingerprint(guesses, targets)
+            ))
+    }
+
+    /// Fails to compile for words too long for their statuses to fit in a cell.
+    fn check_length() {
+        const {
+            assert!(
+                GuessStatusN::<N>::COUNT <= 256,
+                "words are too long for a matrix"
+            )
+        }
+    }
+
+    pub fn build(guesses: &[WordN<N>], targets: &[WordN<N>]) -> FeedbackMatrix<N> {
+        FeedbackMatrix::<N>::check_length();
+        let mut cells = vec![0; guesses.len() * targets.len()];
+        if !targets.is_empty() {
+            cells
+                .par_chunks_mut(targets.len())
+                .zip(guesses.par_iter())
+                .for_each(|(row, &guess)| {
+                    for (cell, target) in row.iter_mut().zip(targets) {
+                        *cell = WordGuessN::guess_from(guess, target).status.index() as u8;
+                    }
+                });
+        }
+        FeedbackMatrix {
+            guesses: guesses.to_vec(),
+            targets: targets.to_vec(),
+            guess_index: index(guesses),
+            target_index: index(targets),
+            cells,
+        }
+    }
+
+    /// Loads a matrix saved by [`FeedbackMatrix::save`], or `None` if there's no cache file or
+    /// it was built from different word lists.
+    pub fn load(
+        path: &Path,
+        guesses: &[WordN<N>],
+        targets: &[WordN<N>],
+    ) -> io::Result<Option<FeedbackMatrix<N>>> {
+        FeedbackMatrix::<N>::check_length();
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut header = [0; 16];
+        match file.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        if &header[..8] != MAGIC || header[8..] != fingerprint(guesses, targets).to_le_bytes() {
+            return Ok(None);
+        }
+        let mut cells = Vec::with_capacity(guesses.len() * targets.len());
+        file.read_to_end(&mut cells)?;
+        if cells.len() != guesses.len() * targets.len() {
+            return Ok(None);
+        }
+        Ok(Some(FeedbackMatrix {
+            guesses: guesses.to_vec(),
+            targets: targets.to_vec(),
+            guess_index: index(guesses),
+            target_index: index(targets),
+            cells,
+        }))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&fingerprint(&self.guesses, &self.targets).to_le_bytes())?;
+        file.write_all(&self.cells)?;
+        file.flush()
+    }
+
+    /// Loads the matrix from `path`, building and saving it there first if need be.
+    pub fn cached(
+        path: &Path,
+        guesses: &[WordN<N>],
+        targets: &[WordN<N>],
+    ) -> io::Result<FeedbackMatrix<N>> {
+        match FeedbackMatrix::load(path, guesses, targets)? {
+            Some(matrix) => Ok(matrix),
+            None => {
+                let matrix = FeedbackMatrix::build(guesses, targets);
+                matrix.save(path)?;
+                Ok(matrix)
+            }
+        }
+    }
+
+    pub fn guesses(&self) -> &[WordN<N>] {
+        &self.guesses
+    }
+
+    pub fn targets(&self) -> &[WordN<N>] {
+        &self.targets
+    }
+
+    pub fn guess_index(&self, guess: WordN<N>) -> Option<usize> {
+        self.guess_index.get(&guess).copied()
+    }
+
+    pub fn target_index(&self, target: WordN<N>) -> Option<usize> {
+        self.target_index.get(&target).copied()
+    }
+
+    /// The encoded status of the guess at index `guess` for each target, in target order.
+    pub fn row(&self, guess: usize) -> &[u8] {
+        let width = self.targets.len();
+        &self.cells[guess * width..(guess + 1) * width]
+    }
+
+    /// The status `guess` gets against `target`, if both are in the matrix.
+    pub fn status(&self, guess: WordN<N>, target: WordN<N>) -> Option<GuessStatusN<N>> {
+        let row = self.row(self.guess_index(guess)?);
+        Some(GuessStatusN::from_index(
+            row[self.target_index(target)?] as usize,
+        ))
+    }
+
+    /// The indices of the `targets` that give the guess at index `guess` the `status`.
+    pub fn filter(&self, guess: usize, status: GuessStatusN<N>, targets: &[usize]) -> Vec<usize> {
+        let row = self.row(guess);
+        let code = status.index() as u8;
+        targets
+            .iter()
+            .copied()
+            .filter(|&t| row[t] == code)
+            .collect()
+    }
+
+    /// The targets that give `guess` the `status`, in target order, or `None` if the guess
+    /// isn't in the matrix.
+    pub fn matching(&self, guess: WordN<N>, status: GuessStatusN<N>) -> Option<Vec<WordN<N>>> {
+        let all: Vec<usize> = (0..self.targets.len()).collect();
+        Some(
+            self.filter(self.guess_index(guess)?, status, &all)
+                .into_iter()
+                .map(|t| self.targets[t])
+                .collect(),
+        )
+    }
+
+    /// The sizes of the non-empty buckets the guess at index `guess` splits `targets` into.
+    pub fn sizes(&self, guess: usize, targets: &[usize]) -> Vec<usize> {
+        let row = self.row(guess);
+        let mut histogram = vec![0; GuessStatusN::<N>::COUNT];
+        for &t in targets {
+            histogram[row[t] as usize] += 1;
+        }
+        sizes(histogram)
+    }
+
+    /// Like [`crate::solver::rank_guesses`], but reading the statuses from the matrix.
+    ///
+    /// Returns `None` if any of the words aren't in the matrix.
+    pub fn rank_guesses<M>(
+        &self,
+        guesses: &[WordN<N>],
+        candidates: &[WordN<N>],
+        metric: &M,
+    ) -> Option<Vec<ScoredGuess<N>>>
+    where
+        M: Metric + ?Sized,
+    {
+        let guess_indices = guesses
+            .iter()
+            .map(|&g| self.guess_index(g))
+            .collect::<Option<Vec<usize>>>()?;
+        let target_indices = candidates
+            .iter()
+            .map(|&t| self.target_index(t))
+            .collect::<Option<Vec<usize>>>()?;
+        let mut scored: Vec<ScoredGuess<N>> = guesses
+            .par_iter()
+            .zip(guess_indices.par_iter())
+            .map(|(&guess, &index)| ScoredGuess {
+                guess,
+                score: metric.score(&self.sizes(index, &target_indices)),
+                candidate: candidates.contains(&guess),
+            })
+            .collect();
+        scored.sort_by(compare);
+        Some(scored)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::matrix::FeedbackMatrix;
+    use crate::solver::{rank_guesses, Entropy};
+    use crate::words::TARGET_WORDS;
+    use crate::{GuessStatus, Word, WordGuess};
+    use anyhow::Error;
+
+    #[test]
+    fn matrix_agrees_with_scoring() {
+        let matrix = FeedbackMatrix::build(&TARGET_WORDS[..100], &TARGET_WORDS[100..300]);
+        for &guess in &TARGET_WORDS[..100] {
+            for &target in &TARGET_WORDS[100..300] {
+                assert_eq!(
+                    matrix.status(guess, target),
+                    Some(WordGuess::guess(guess, target).status)
+                );
+            }
+        }
+        assert_eq!(matrix.status(TARGET_WORDS[100], TARGET_WORDS[0]), None);
+    }
+
+    #[test]
+    fn matrix_filters_like_scoring() -> Result<(), Error> {
+        let matrix = FeedbackMatrix::build(&TARGET_WORDS[..100], &TARGET_WORDS[100..300]);
+        let guess = TARGET_WORDS[3];
+        let status = GuessStatus::try_from("-+---")?;
+        let expected: Vec<Word> = TARGET_WORDS[100..300]
+            .iter()
+            .copied()
+            .filter(|&t| WordGuess::guess(guess, t).status == status)
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(matrix.matching(guess, status), Some(expected));
+        assert_eq!(matrix.matching(TARGET_WORDS[100], status), None);
+        Ok(())
+    }
+
+    #[test]
+    fn matrix_ranks_like_scoring() {
+        let guesses = &TARGET_WORDS[..200];
+        let targets = &TARGET_WORDS[100..400];
+        let matrix = FeedbackMatrix::build(guesses, targets);
+        assert_eq!(
+            matrix.rank_guesses(guesses, targets, &Entropy),
+            Some(rank_guesses(guesses, targets, &Entropy))
+        );
+    }
+
+    #[test]
+    fn cache_is_checked_against_word_lists() -> Result<(), Error> {
+        let path = std::env::temp_dir().join(format!("wordle-test-{}.bin", std::process::id()));
+        let guesses = &TARGET_WORDS[..20];
+        let targets = &TARGET_WORDS[20..50];
+        let matrix = FeedbackMatrix::cached(&path, guesses, targets)?;
+        let loaded = FeedbackMatrix::load(&path, guesses, targets)?.unwrap();
+        assert_eq!(loaded.cells, matrix.cells);
+        assert!(FeedbackMatrix::load(&path, targets, guesses)?.is_none());
+        assert!(FeedbackMatrix::load(&path, &[Word::try_from("cigar")?], targets)?.is_none());
+        std::fs::remove_file(&path)?;
+        assert!(FeedbackMatrix::load(&path, guesses, targets)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn word_lists_are_cached_apart() {
+        let guesses = &TARGET_WORDS[..20];
+        let targets = &TARGET_WORDS[20..50];
+        let path = FeedbackMatrix::default_cache_path(guesses, targets);
+        assert_eq!(path, FeedbackMatrix::default_cache_path(guesses, targets));
+        assert_ne!(path, FeedbackMatrix::default_cache_path(targets, guesses));
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("feedback-") && name.ends_with(".bin"));
+        assert_eq!(name.len(), "feedback-.bin".len() + 16);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::format::{Format, InFormat};
+use crate::game::Game;
+use crate::{GuessStatus, InPalette, Palette, WordError};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The result of a game as it's shared, like
+///
+/// ```text
+/// Wordle 1,234 3/6*
+///
+/// ⬛🟨⬛⬛⬛
+/// ⬛🟩🟨⬛🟩
+/// 🟩🟩🟩🟩🟩
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Share {
+    pub puzzle: usize,
+    /// How many guesses it took, or `None` for a game that was lost.
+    pub score: Option<usize>,
+    pub max_guesses: usize,
+    pub hard_mode: bool,
+    pub rows: Vec<GuessStatus>,
+}
+
+impl Share {
+    pub fn from_game(puzzle: usize, game: &Game) -> Share {
+        Share {
+            puzzle,
+            score: game.is_won().then(|| game.guesses().len()),
+            max_guesses: game.max_guesses(),
+            hard_mode: game.hard_mode(),
+            rows: game.guesses().iter().map(|wg| wg.status).collect(),
+        }
+    }
+}
+
+/// Parses a puzzle number, with or without commas between groups of three digits.
+fn puzzle(s: &str) -> Option<usize> {
+    let mut groups = s.split(',');
+    let first = groups.next()?;
+    let mut digits = first.to_string();
+    let mut grouped = false;
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        grouped = true;
+        digits.push_str(group);
+    }
+    if first.is_empty()
+        || (grouped && first.len() > 3)
+        || !digits.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Parses a score like `3/6`, `X/6` or `3/6*`.
+fn score(s: &str) -> Option<(Option<usize>, usize, bool)> {
+    let (s, hard_mode) = match s.strip_suffix('*') {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let (score, max) = s.split_once('/')?;
+    let max: usize = max.parse().ok()?;
+    let score = match score {
+        "X" | "x" => None,
+        n => Some(n.parse().ok().filter(|&n| n >= 1 && n <= max)?),
+    };
+    Some((score, max, hard_mode))
+}
+
+/// Finds `Wordle <puzzle> <score>` in `line`, returning whatever follows it.
+fn header(line: &str) -> Option<(usize, Option<usize>, usize, bool, &str)> {
+    line.match_indices("Wordle").find_map(|(i, _)| {
+        let rest = line[i..].strip_prefix("Wordle")?;
+        let rest = rest.strip_prefix(char::is_whitespace)?.trim_start();
+        let (number, rest) = rest.split_once(char::is_whitespace)?;
+        let rest = rest.trim_start();
+        // Pasting shortcodes can run the first row straight onto the score.
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == ':')
+            .unwrap_or(rest.len());
+        let (scored, rest) = rest.split_at(end);
+        let (score, max, hard_mode) = score(scored)?;
+        Some((puzzle(number)?, score, max, hard_mode, rest))
+    })
+}
+
+/// Reads a share out of a message, ignoring any text before the `Wordle` header and after the
+/// rows.  The number of rows has to agree with the score.
+impl FromStr for Share {
+    type Err = WordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let (puzzle, score, max_guesses, hard_mode, rest) = lines
+            .by_ref()
+            .find_map(header)
+            .ok_or(WordError::NotWordle)?;
+
+        let mut rows: Vec<GuessStatus> = GuessStatus::try_from(rest.trim()).into_iter().collect();
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                if rows.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            match GuessStatus::try_from(line) {
+                Ok(status) => rows.push(status),
+                Err(_) => break,
+            }
+        }
+
+        let expected = score.unwrap_or(max_guesses);
+        if rows.len() != expected {
+            return Err(WordError::Rows(expected, rows.len()));
+        }
+        Ok(Share {
+            puzzle,
+            score,
+            max_guesses,
+            hard_mode,
+            rows,
+        })
+    }
+}
+
+struct Grouped(usize);
+
+impl Display for Grouped {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0 < 1000 {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "{},{:03}", Grouped(self.0 / 1000), self.0 % 1000)
+        }
+    }
+}
+
+impl Display for Share {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.in_format(Format::default()), f)
+    }
+}
+
+impl Share {
+    pub fn in_palette(&self, palette: Palette) -> InPalette<&Share> {
+        InPalette(self, palette)
+    }
+
+    pub fn in_format(&self, format: Format) -> InFormat<&Share> {
+        InFormat(self, format)
+    }
+}
+
+impl Display for InPalette<&Share> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0.in_format(Format::Emoji(self.1)), f)
+    }
+}
+
+impl Display for InFormat<&Share> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let InFormat(share, format) = *self;
+        write!(f, "Wordle {} ", Grouped(share.puzzle))?;
+        match share.score {
+            Some(score) => write!(f, "{}", score)?,
+            None => write!(f, "X")?,
+        }
+        write!(
+            f,
+            "/{}{}",
+            share.max_guesses,
+            if share.hard_mode { "*" } else { "" }
+        )?;
+        writeln!(f)?;
+        for row in &share.rows {
+            write!(f, "\n{}", row.in_format(format))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::share::Share;
+    use crate::{GuessStatus, Palette, WordError};
+    use anyhow::Error;
+
+    #[test]
+    fn share_is_parsed() -> Result<(), Error> {
+        let share: Share = "Wordle 232 3/6\n\n⬛🟨⬛⬛⬛\n⬛🟩🟨⬛🟩\n🟩🟩🟩🟩🟩".parse()?;
+        assert_eq!(share.puzzle, 232);
+        assert_eq!(share.score, Some(3));
+        assert_eq!(share.max_guesses, 6);
+        assert!(!share.hard_mode);
+        assert_eq!(share.rows[1], GuessStatus::try_from("-=+-=")?);
+        Ok(())
+    }
+
+    #[test]
+    fn share_tolerates_chat() -> Result<(), Error> {
+        let text = "Phew, close one!  Wordle 1,234 X/6*:black_large_square:\
+            :black_large_square::black_large_square::black_large_square::black_large_square:\n\
+            ⬛⬛⬛⬛⬛\n⬛⬛⬛⬛⬛\n⬛⬛⬛⬛⬛\n⬛⬛⬛⬛⬛\n🟩🟩🟩🟩⬛\n\nBetter luck tomorrow";
+        let share: Share = text.parse()?;
+        assert_eq!(share.puzzle, 1234);
+        assert_eq!(share.score, None);
+        assert!(share.hard_mode);
+        assert_eq!(share.rows.len(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn share_must_be_consistent() {
+        assert!(matches!(
+            "Wordle 232 4/6\n\n⬛🟨⬛⬛⬛\n🟩🟩🟩🟩🟩".parse::<Share>(),
+            Err(WordError::Rows(4, 2))
+        ));
+        assert!(matches!(
+            "I played Wordle today".parse::<Share>(),
+            Err(WordError::NotWordle)
+        ));
+        assert!(matches!(
+            "Wordle 12,34 1/6\n\n🟩🟩🟩🟩🟩".parse::<Share>(),
+            Err(WordError::NotWordle)
+        ));
+    }
+
+    #[test]
+    fn share_round_trips() -> Result<(), Error> {
+        let text = "Wordle 1,001 2/6*\n\n⬛🟨⬛⬛⬛\n🟩🟩🟩🟩🟩";
+        let share: Share = text.parse()?;
+        assert_eq!(share.to_string(), text);
+        assert_eq!(share.to_string().parse::<Share>()?, share);
+        let light = share.in_palette(Palette::HighContrastLight).to_string();
+        assert_eq!(light, "Wordle 1,001 2/6*\n\n⬜🟦⬜⬜⬜\n🟧🟧🟧🟧🟧");
+        assert_eq!(light.parse::<Share>()?, share);
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+use crate::hard_mode;
+use crate::knowledge::Knowledge;
+use crate::matrix::FeedbackMatrix;
+use crate::words::TARGET_WORDS;
+use crate::{GuessStatusN, Strategy, WordGuessN, WordN};
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// A guess together with how well it splits the remaining candidates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScoredGuess<const N: usize = 5> {
+    pub guess: WordN<N>,
+    /// The score given by the [`Metric`] used for ranking; higher is better.
+    pub score: f64,
+    /// Whether the guess could itself be the target.
+    pub candidate: bool,
+}
+
+/// Returns the words in `words` that are consistent with every guess in `history`.
+pub fn candidates<'a, I, const N: usize>(words: I, history: &[WordGuessN<N>]) -> Vec<WordN<N>>
+where
+    I: IntoIterator<Item = &'a WordN<N>>,
+{
+    let knowledge = Knowledge::from_history(history);
+    words
+        .into_iter()
+        .filter(|&target| knowledge.matches(target))
+        .copied()
+        .collect()
+}
+
+/// Counts how many of the `candidates` would produce each status for `guess`.
+pub fn partition<const N: usize>(
+    guess: WordN<N>,
+    candidates: &[WordN<N>],
+) -> HashMap<GuessStatusN<N>, usize> {
+    let mut buckets = HashMap::new();
+    for target in candidates {
+        *buckets
+            .entry(WordGuessN::guess_from(guess, target).status)
+            .or_insert(0) += 1;
+    }
+    buckets
+}
+
+/// A way of judging how well a guess splits the candidates.
+pub trait Metric: Debug + Sync {
+    /// Scores a guess from the sizes of the non-empty buckets it partitions the candidates
+    /// into.  Higher scores are better.
+    fn score(&self, sizes: &[usize]) -> f64;
+
+    /// Describes a score produced by this metric for people to read.
+    fn describe(&self, score: f64) -> String;
+}
+
+/// Maximise the Shannon entropy of the feedback.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Entropy;
+
+/// Minimise the size of the largest bucket.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Minimax;
+
+/// Minimise the expected number of candidates left after guessing.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ExpectedSize;
+
+/// Maximise the number of distinct outcomes.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Buckets;
+
+impl Metric for Entropy {
+    fn score(&self, sizes: &[usize]) -> f64 {
+        let total: usize = sizes.iter().sum();
+        let total = total as f64;
+        sizes
+            .iter()
+            .map(|&n| {
+                let p = n as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    fn describe(&self, score: f64) -> String {
+        format!("{:.3} bits", score)
+    }
+}
+
+impl Metric for Minimax {
+    fn score(&self, sizes: &[usize]) -> f64 {
+        -(sizes.iter().copied().max().unwrap_or(0) as f64)
+    }
+
+    fn describe(&self, score: f64) -> String {
+        format!("at most {} left", -score)
+    }
+}
+
+impl Metric for ExpectedSize {
+    fn score(&self, sizes: &[usize]) -> f64 {
+        let total: usize = sizes.iter().sum();
+        let squares: usize = sizes.iter().map(|n| n * n).sum();
+        -(squares as f64 / total as f64)
+    }
+
+    fn describe(&self, score: f64) -> String {
+        format!("{:.3} left on average", -score)
+    }
+}
+
+impl Metric for Buckets {
+    fn score(&self, sizes: &[usize]) -> f64 {
+        sizes.len() as f64
+    }
+
+    fn describe(&self, score: f64) -> String {
+        format!("{} outcomes", score)
+    }
+}
+
+/// The sizes of the non-empty buckets in a histogram indexed by [`GuessStatusN::index`],
+/// smallest first.
+pub(crate) fn sizes(histogram: Vec<usize>) -> Vec<usize> {
+    let mut sizes: Vec<usize> = histogram.into_iter().filter(|&n| n > 0).collect();
+    sizes.sort_unstable();
+    sizes
+}
+
+fn histogram<const N: usize>(guess: WordN<N>, candidates: &[WordN<N>]) -> Vec<usize> {
+    let mut histogram = vec![0; GuessStatusN::<N>::COUNT];
+    for target in candidates {
+        histogram[WordGuessN::guess_from(guess, target).status.index()] += 1;
+    }
+    histogram
+}
+
+/// The Shannon entropy, in bits, of the feedback `guess` would get against `candidates`.
+pub fn entropy<const N: usize>(guess: WordN<N>, candidates: &[WordN<N>]) -> f64 {
+    Entropy.score(&sizes(histogram(guess, candidates)))
+}
+
+/// Scores every word in `guesses` against `candidates` using `metric`, best first.
+///
+/// Ties are broken in favour of guesses that might be the answer, then alphabetically.
+pub fn rank_guesses<M, const N: usize>(
+    guesses: &[WordN<N>],
+    candidates: &[WordN<N>],
+    metric: &M,
+) -> Vec<ScoredGuess<N>>
+where
+    M: Metric + ?Sized,
+{
+    let mut scored: Vec<ScoredGuess<N>> = guesses
+        .par_iter()
+        .map(|&guess| ScoredGuess {
+            guess,
+            score: metric.score(&sizes(histogram(guess, candidates))),
+            candidate: candidates.contains(&guess),
+        })
+        .collect();
+    scored.sort_by(compare);
+    scored
+}
+
+pub(crate) fn compare<const N: usize>(a: &ScoredGuess<N>, b: &ScoredGuess<N>) -> Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then(b.candidate.cmp(&a.candidate))
+        .then(a.guess.cmp(&b.guess))
+}
+
+/// Always guesses the first target that is still possible.
+#[derive(Clone, Debug)]
+pub struct Naive<const N: usize = 5> {
+    targets: Vec<WordN<N>>,
+}
+
+impl<const N: usize> Naive<N> {
+    pub fn new(targets: &[WordN<N>]) -> Naive<N> {
+        Naive {
+            targets: targets.to_vec(),
+        }
+    }
+}
+
+impl Default for Naive {
+    fn default() -> Self {
+        Naive::new(&TARGET_WORDS)
+    }
+}
+
+impl<const N: usize> Strategy<N> for Naive<N> {
+    fn next_guess(&self, history: &[WordGuessN<N>]) -> Option<WordN<N>> {
+        let knowledge = Knowledge::from_history(history);
+        self.targets
+            .iter()
+            .copied()
+            .find(|target| knowledge.matches(target))
+    }
+}
+
+/// Guesses whichever word the [`Metric`] ranks best against the remaining targets.
+#[derive(Clone, Debug)]
+pub struct Ranked<M, const N: usize = 5> {
+    metric: M,
+    targets: Vec<WordN<N>>,
+    guesses: Vec<WordN<N>>,
+    hard_mode: bool,
+    matrix: Option<Arc<FeedbackMatrix<N>>>,
+}
+
+impl<M: Metric, const N: usize> Ranked<M, N> {
+    pub fn new(metric: M, targets: &[WordN<N>], guesses: &[WordN<N>]) -> Ranked<M, N> {
+        Ranked {
+            metric,
+            targets: targets.to_vec(),
+            guesses: guesses.to_vec(),
+            hard_mode: false,
+            matrix: None,
+        }
+    }
+
+    /// Only consider guesses that keep to the hard mode rules.
+    pub fn with_hard_mode(self, hard_mode: bool) -> Ranked<M, N> {
+        Ranked { hard_mode, ..self }
+    }
+
+    /// Look statuses up in `matrix` rather than working them out, where it has them.
+    pub fn with_matrix(self, matrix: Arc<FeedbackMatrix<N>>) -> Ranked<M, N> {
+        Ranked {
+            matrix: Some(matrix),
+            ..self
+        }
+    }
+}
+
+impl<M: Metric + Default> Default for Ranked<M> {
+    fn default() -> Self {
+        Ranked::new(M::default(), &TARGET_WORDS, &TARGET_WORDS)
+    }
+}
+
+impl<M: Metric, const N: usize> Strategy<N> for Ranked<M, N> {
+    fn next_guess(&self, history: &[WordGuessN<N>]) -> Option<WordN<N>> {
+        let remaining = candidates(self.targets.iter(), history);
+        if remaining.len() <= 2 {
+            return remaining.first().copied();
+        }
+        let allowed: Vec<WordN<N>>;
+        let guesses = if self.hard_mode {
+            allowed = self
+                .guesses
+                .iter()
+                .copied()
+                .filter(|&guess| hard_mode::check(history, guess).is_ok())
+                .collect();
+            &allowed
+        } else {
+            &self.guesses
+        };
+        self.matrix
+            .as_ref()
+            .and_then(|matrix| matrix.rank_guesses(guesses, &remaining, &self.metric))
+            .unwrap_or_else(|| rank_guesses(guesses, &remaining, &self.metric))
+            .first()
+            .map(|g| g.guess)
+    }
+}
+
+/// Plays `strategy` against `target`, starting with `opener` if there is one.
+///
+/// Stops once the target is found, the strategy gives up, or after `limit` guesses.
+pub fn play<S, const N: usize>(
+    strategy: &S,
+    opener: Option<WordN<N>>,
+    target: WordN<N>,
+    limit: usize,
+) -> Vec<WordGuessN<N>>
+where
+    S: Strategy<N> + ?Sized,
+{
+    let mut history: Vec<WordGuessN<N>> = vec![];
+    while history.len() < limit {
+        let guess = match (history.is_empty(), opener) {
+            (true, Some(opener)) => opener,
+            _ => match strategy.next_guess(&history) {
+                Some(guess) => guess,
+                None => break,
+            },
+        };
+        history.push(WordGuessN::guess(guess, target));
+        if guess == target {
+            break;
+        }
+    }
+    history
+}
+
+/// Plays `strategy` against each of the `targets` in parallel, returning the games in the same
+/// order as the targets.
+pub fn benchmark<S, const N: usize>(
+    strategy: &S,
+    opener: Option<WordN<N>>,
+    targets: &[WordN<N>],
+    limit: usize,
+) -> Vec<Vec<WordGuessN<N>>>
+where
+    S: Strategy<N> + ?Sized,
+{
+    targets
+        .par_iter()
+        .map(|&target| play(strategy, opener, target, limit))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hard_mode::first_violation;
+    use crate::solver::{
+        benchmark, candidates, entropy, play, rank_guesses, Buckets, Entropy, ExpectedSize, Metric,
+        Minimax, Naive, Ranked,
+    };
+    use crate::word_list::WordList;
+    use crate::words::TARGET_WORDS;
+    use crate::{GuessStatus, Strategy, Word, WordGuess, WordGuessN};
+    use anyhow::Error;
+
+    #[test]
+    fn candidates_match_history() -> Result<(), Error> {
+        let target = Word::try_from("those")?;
+        let history = [WordGuess::guess(Word::try_from("cigar")?, target)];
+        let remaining = candidates(TARGET_WORDS.iter(), &history);
+        assert!(remaining.contains(&target));
+        assert!(!remaining.contains(&Word::try_from("cigar")?));
+        assert!(remaining
+            .iter()
+            .all(
+                |w| WordGuess::guess(Word::try_from("cigar").unwrap(), *w).status
+                    == GuessStatus::try_from("-----").unwrap()
+            ));
+        Ok(())
+    }
+
+    #[test]
+    fn entropy_of_perfect_split() -> Result<(), Error> {
+        let words = [Word::try_from("cigar")?, Word::try_from("humph")?];
+        assert_eq!(entropy(words[0], &words), 1.0);
+        Ok(())
+    }
+
+    #[test]
+    fn entropy_of_useless_guess() -> Result<(), Error> {
+        let words = [Word::try_from("cigar")?, Word::try_from("rebut")?];
+        assert_eq!(entropy(Word::try_from("fjord")?, &words), 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn rank_prefers_candidates_on_ties() -> Result<(), Error> {
+        let candidates = [Word::try_from("cigar")?, Word::try_from("humph")?];
+        let guesses = [Word::try_from("hippo")?, candidates[1], candidates[0]];
+        let ranked = rank_guesses(&guesses, &candidates, &Entropy);
+        assert_eq!(ranked[0].guess, candidates[0]);
+        assert_eq!(ranked[1].guess, candidates[1]);
+        assert!(!ranked[2].candidate);
+        Ok(())
+    }
+
+    #[test]
+    fn metrics_score_buckets() {
+        let sizes = [1, 3, 4];
+        assert_eq!(Entropy.score(&[1, 1]), 1.0);
+        assert_eq!(Minimax.score(&sizes), -4.0);
+        assert_eq!(ExpectedSize.score(&sizes), -26.0 / 8.0);
+        assert_eq!(Buckets.score(&sizes), 3.0);
+    }
+
+    #[test]
+    fn metrics_rank_best_first() -> Result<(), Error> {
+        let candidates = candidates(
+            TARGET_WORDS.iter(),
+            &[WordGuess::new(
+                Word::try_from("raise")?,
+                GuessStatus::try_from("-----")?,
+            )],
+        );
+        let guesses: Vec<Word> = TARGET_WORDS[..200].to_vec();
+        let metrics: [&dyn Metric; 4] = [&Entropy, &Minimax, &ExpectedSize, &Buckets];
+        for metric in metrics {
+            let ranked = rank_guesses(&guesses, &candidates, metric);
+            assert_eq!(ranked.len(), guesses.len());
+            assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn naive_guesses_first_candidate() -> Result<(), Error> {
+        let naive = Naive::default();
+        assert_eq!(naive.next_guess(&[]), Some(TARGET_WORDS[0]));
+        let history = [WordGuess::guess(TARGET_WORDS[0], Word::try_from("those")?)];
+        let next = naive.next_guess(&history).unwrap();
+        assert_eq!(candidates([next].iter(), &history), vec![next]);
+        Ok(())
+    }
+
+    #[test]
+    fn strategy_gives_up_without_candidates() -> Result<(), Error> {
+        let history = [WordGuess::new(
+            Word::try_from("cigar")?,
+            GuessStatus::try_from("====-")?,
+        )];
+        assert_eq!(Naive::default().next_guess(&history), None);
+        assert_eq!(Ranked::<Entropy>::default().next_guess(&history), None);
+        Ok(())
+    }
+
+    #[test]
+    fn play_finds_target() -> Result<(), Error> {
+        let target = Word::try_from("those")?;
+        let targets = &TARGET_WORDS[..300];
+        let strategy = Ranked::new(Minimax, targets, targets);
+        let history = play(&strategy, Some(Word::try_from("raise")?), target, 6);
+        assert_eq!(history[0].word(), Word::try_from("raise")?);
+        assert_eq!(history.last().unwrap().word(), target);
+        Ok(())
+    }
+
+    #[test]
+    fn benchmark_plays_every_target() {
+        let targets = &TARGET_WORDS[..50];
+        let games = benchmark(&Naive::new(targets), None, targets, 10);
+        assert_eq!(games.len(), targets.len());
+        for (game, target) in games.iter().zip(targets) {
+            assert_eq!(game.last().unwrap().word(), *target);
+        }
+        assert_eq!(games[0].len(), 1);
+    }
+
+    #[test]
+    fn hard_mode_strategy_keeps_to_the_rules() -> Result<(), Error> {
+        let targets = &TARGET_WORDS[..300];
+        let strategy = Ranked::new(Entropy, targets, &TARGET_WORDS).with_hard_mode(true);
+        for &target in &targets[..10] {
+            let game = play(&strategy, Some(Word::try_from("raise")?), target, 20);
+            assert_eq!(first_violation(&game), None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn other_lengths_are_solved() -> Result<(), Error> {
+        let targets = WordList::<4>::parse("bark cork dork fork lark park pork work")?;
+        let strategy = Ranked::new(Entropy, targets.words(), targets.words());
+        let games = benchmark(&strategy, None, targets.words(), 10);
+        for (game, target) in games.iter().zip(targets.words()) {
+            assert_eq!(game.last().unwrap().word(), *target);
+        }
+        let history = [WordGuessN::guess(
+            targets.word("pork")?,
+            targets.word("fork")?,
+        )];
+        assert_eq!(candidates(targets.words(), &history).len(), 4);
+        Ok(())
+    }
+}
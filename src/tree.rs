@@ -0,0 +1,241 @@
+use crate::format::{Format, InFormat};
+use crate::solver::partition;
+use crate::word_list::WordList;
+use crate::{GuessStatusN, Strategy, WordError, WordGuessN, WordN};
+use rayon::prelude::*;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+
+/// What to guess next for every outcome, worked out in advance.
+///
+/// A tree is itself a [`Strategy`], so once built or loaded from JSON it solves without
+/// any further search.  Deserializing checks the guesses against the built-in lists, and
+/// [`DecisionTree::from_json`] against any other, of words of any length `N`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DecisionTree<const N: usize = 5> {
+    pub guess: WordN<N>,
+    /// The subtree to follow for each status the guess may get, other than all correct.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub children: BTreeMap<GuessStatusN<N>, DecisionTree<N>>,
+}
+
+/// A tree as it's saved, before its guesses are checked against a word list.
+#[derive(Deserialize)]
+struct Saved<const N: usize> {
+    guess: String,
+    #[serde(default)]
+    children: BTreeMap<GuessStatusN<N>, Saved<N>>,
+}
+
+impl<const N: usize> Saved<N> {
+    fn resolve(self, words: &WordList<N>) -> Result<DecisionTree<N>, WordError> {
+        Ok(DecisionTree {
+            guess: words.word(&self.guess)?,
+            children: self
+                .children
+                .into_iter()
+                .map(|(status, child)| Ok((status, child.resolve(words)?)))
+                .collect::<Result<_, WordError>>()?,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for DecisionTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = Saved::deserialize(deserializer)?;
+        saved
+            .resolve(&WordList::embedded())
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<const N: usize> DecisionTree<N> {
+    /// Reads a tree written as JSON, whose guesses all have to be on `words`.
+    pub fn from_json<R: Read>(
+        reader: R,
+        words: &WordList<N>,
+    ) -> serde_json::Result<DecisionTree<N>> {
+        let saved: Saved<N> = serde_json::from_reader(reader)?;
+        saved.resolve(words).map_err(de::Error::custom)
+    }
+
+    /// Builds the tree `strategy` plays against `targets`, starting with `opener`.
+    ///
+    /// If the strategy gives up, or suggests a guess that can't narrow things down, the tree
+    /// guesses the first remaining target instead so that every target is eventually solved.
+    pub fn build<S>(strategy: &S, opener: WordN<N>, targets: &[WordN<N>]) -> DecisionTree<N>
+    where
+        S: Strategy<N> + ?Sized,
+    {
+        DecisionTree::grow(strategy, opener, &[], targets)
+    }
+
+    fn grow<S>(
+        strategy: &S,
+        guess: WordN<N>,
+        history: &[WordGuessN<N>],
+        remaining: &[WordN<N>],
+    ) -> Self
+    where
+        S: Strategy<N> + ?Sized,
+    {
+        let mut buckets: BTreeMap<GuessStatusN<N>, Vec<WordN<N>>> = BTreeMap::new();
+        for target in remaining {
+            buckets
+                .entry(WordGuessN::guess_from(guess, target).status)
+                .or_default()
+                .push(*target);
+        }
+        let children = buckets
+            .into_par_iter()
+            .filter(|(status, _)| *status != GuessStatusN::SOLVED)
+            .map(|(status, bucket)| {
+                let mut history = history.to_vec();
+                history.push(WordGuessN::new(guess, status));
+                let next = strategy
+                    .next_guess(&history)
+                    .filter(|&next| bucket.contains(&next) || partition(next, &bucket).len() > 1)
+                    .unwrap_or(bucket[0]);
+                (
+                    status,
+                    DecisionTree::grow(strategy, next, &history, &bucket),
+                )
+            })
+            .collect();
+        DecisionTree { guess, children }
+    }
+
+    /// The most guesses the tree needs to solve any of its targets.
+    pub fn depth(&self) -> usize {
+        1 + self
+            .children
+            .values()
+            .map(DecisionTree::<N>::depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn in_format(&self, format: Format) -> InFormat<&DecisionTree<N>> {
+        InFormat(self, format)
+    }
+
+    fn write_indented(
+        &self,
+        f: &mut Formatter<'_>,
+        format: Format,
+        indent: usize,
+    ) -> std::fmt::Result {
+        for (status, child) in &self.children {
+            writeln!(
+                f,
+                "{:indent$}{} {}",
+                "",
+                status.in_format(format),
+                child.guess,
+                indent = indent
+            )?;
+            child.write_indented(f, format, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> Display for DecisionTree<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.in_format(Format::default()), f)
+    }
+}
+
+impl<const N: usize> Display for InFormat<&DecisionTree<N>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.0.guess)?;
+        self.0.write_indented(f, self.1, 2)
+    }
+}
+
+impl<const N: usize> Strategy<N> for DecisionTree<N> {
+    fn next_guess(&self, history: &[WordGuessN<N>]) -> Option<WordN<N>> {
+        let mut node = self;
+        for wg in history {
+            if wg.word() != node.guess {
+                return None;
+            }
+            node = node.children.get(&wg.status)?;
+        }
+        Some(node.guess)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::solver::{play, Naive};
+    use crate::tree::DecisionTree;
+    use crate::word_list::WordList;
+    use crate::words::TARGET_WORDS;
+    use crate::Word;
+    use anyhow::Error;
+
+    fn tree() -> Result<DecisionTree, Error> {
+        let targets = &TARGET_WORDS[..100];
+        Ok(DecisionTree::build(
+            &Naive::new(targets),
+            Word::try_from("raise")?,
+            targets,
+        ))
+    }
+
+    #[test]
+    fn tree_solves_every_target() -> Result<(), Error> {
+        let tree = tree()?;
+        for &target in &TARGET_WORDS[..100] {
+            let game = play(&tree, None, target, tree.depth());
+            assert_eq!(game.last().unwrap().word(), target);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tree_round_trips_through_json() -> Result<(), Error> {
+        let tree = tree()?;
+        let json = serde_json::to_string(&tree)?;
+        assert!(json.starts_with(r#"{"guess":"raise","children":{"#));
+        assert_eq!(serde_json::from_str::<DecisionTree>(&json)?, tree);
+        Ok(())
+    }
+
+    #[test]
+    fn tree_with_other_words_round_trips() -> Result<(), Error> {
+        let list: WordList = WordList::parse("xyzzy cigar plugh rebut frobs")?;
+        let tree =
+            DecisionTree::build(&Naive::new(list.words()), list.word("xyzzy")?, list.words());
+        let json = serde_json::to_string(&tree)?;
+        assert_eq!(DecisionTree::from_json(json.as_bytes(), &list)?, tree);
+        assert!(serde_json::from_str::<DecisionTree>(&json).is_err());
+        assert!(DecisionTree::from_json(json.as_bytes(), &WordList::targets()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn trees_can_have_other_lengths() -> Result<(), Error> {
+        let list = WordList::<4>::parse("bark cork dork fork lark park pork work")?;
+        let tree = DecisionTree::build(&Naive::new(list.words()), list.word("bark")?, list.words());
+        for &target in list.words() {
+            let game = play(&tree, None, target, tree.depth());
+            assert_eq!(game.last().unwrap().word(), target);
+        }
+        let json = serde_json::to_string(&tree)?;
+        assert_eq!(DecisionTree::from_json(json.as_bytes(), &list)?, tree);
+        Ok(())
+    }
+
+    #[test]
+    fn tree_prints_as_text() -> Result<(), Error> {
+        let text = tree()?.to_string();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("raise"));
+        assert!(lines.all(|line| line.starts_with("  ")));
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use crate::alphabet::Alphabet;
+use crate::words::{ListVersion, EXTENDED_WORDS, TARGET_WORDS};
+use crate::{WordError, WordN};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use thiserror::Error;
+
+/// A list of words in a fixed order, like the targets in puzzle order, that can be loaded at
+/// runtime in place of the lists built into [`crate::words`].
+///
+/// The built-in lists are all of five-letter English words, but a loaded list can have words
+/// of any length `N` from any [`Alphabet`].
+#[derive(Clone, Default, Eq, PartialEq)]
+pub struct WordList<const N: usize = 5> {
+    words: Vec<WordN<N>>,
+    index: HashMap<WordN<N>, usize>,
+    alphabet: Alphabet,
+}
+
+#[derive(Debug, Error)]
+pub enum WordListError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Word {0} of the list: {1}")]
+    Word(usize, #[source] WordError),
+}
+
+impl<const N: usize> Debug for WordList<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("WordList({} words)", self.words.len()))
+    }
+}
+
+impl<const N: usize> FromIterator<WordN<N>> for WordList<N> {
+    /// Keeps the first of any repeated words.
+    fn from_iter<T: IntoIterator<Item = WordN<N>>>(iter: T) -> Self {
+        let mut list = WordList::default();
+        for word in iter {
+            if !list.index.contains_key(&word) {
+                list.index.insert(word, list.words.len());
+                list.words.push(word);
+            }
+        }
+        list
+    }
+}
+
+impl WordList {
+    /// The original built-in targets, in puzzle order.
+    pub fn targets() -> WordList {
+        TARGET_WORDS.iter().copied().collect()
+    }
+
+    /// One of the built-in lists of targets, in puzzle order.
+    pub fn version(version: ListVersion) -> WordList {
+        version.targets().iter().copied().collect()
+    }
+
+    /// Every built-in word: the targets, then the extended list.
+    pub fn embedded() -> Arc<WordList> {
+        static EMBEDDED: OnceLock<Arc<WordList>> = OnceLock::new();
+        EMBEDDED
+            .get_or_init(|| {
+                Arc::new(
+                    TARGET_WORDS
+                        .iter()
+                        .chain(EXTENDED_WORDS.iter())
+                        .copied()
+                        .collect(),
+                )
+            })
+            .clone()
+    }
+}
+
+impl<const N: usize> WordList<N> {
+    /// Parses a JSON array of words, or plain text with words separated by whitespace.
+    ///
+    /// Each word is checked like [`crate::Word::try_from`], except that it doesn't have to be
+    /// on the built-in lists and has `N` letters.
+    pub fn parse(text: &str) -> Result<WordList<N>, WordListError> {
+        WordList::parse_in(text, &Alphabet::english())
+    }
+
+    /// Parses a list like [`WordList::parse`], with words spelled in `alphabet`.
+    pub fn parse_in(text: &str, alphabet: &Alphabet) -> Result<WordList<N>, WordListError> {
+        let words: Vec<String> = if text.trim_start().starts_with('[') {
+            serde_json::from_str(text)?
+        } else {
+            text.split_whitespace().map(String::from).collect()
+        };
+        let list: WordList<N> = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| alphabet.word(w).map_err(|e| WordListError::Word(i + 1, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(list.in_alphabet(alphabet))
+    }
+
+    pub fn load(path: &Path) -> Result<WordList<N>, WordListError> {
+        WordList::load_in(path, &Alphabet::english())
+    }
+
+    pub fn load_in(path: &Path, alphabet: &Alphabet) -> Result<WordList<N>, WordListError> {
+        WordList::parse_in(&fs::read_to_string(path)?, alphabet)
+    }
+
+    /// This list, with its words read in `alphabet` from now on.  Each word has to read back
+    /// as the same tiles, like the built-in English lists do in Spanish, but not in an alphabet
+    /// with `ch`, which would read `chair` as four tiles.
+    pub fn spelled_in(self, alphabet: &Alphabet) -> Result<WordList<N>, WordListError> {
+        // Missing tiles are the likelier mistake, so they're reported first.
+        for (i, word) in self.words.iter().enumerate() {
+            if let Some(tile) = word.tiles().iter().find(|&&tile| !alphabet.contains(tile)) {
+                let x = tile.as_str().chars().next().unwrap_or_default();
+                return Err(WordListError::Word(
+                    i + 1,
+                    WordError::Chars(word.to_string(), x),
+                ));
+            }
+        }
+        for (i, word) in self.words.iter().enumerate() {
+            let spelled = word.to_string();
+            match alphabet.word::<N>(&spelled) {
+                Ok(read) if read == *word => {}
+                Ok(_) => return Err(WordListError::Word(i + 1, WordError::Spelling(spelled))),
+                Err(e) => return Err(WordListError::Word(i + 1, e)),
+            }
+        }
+        Ok(self.in_alphabet(alphabet))
+    }
+
+    fn in_alphabet(self, alphabet: &Alphabet) -> WordList<N> {
+        WordList {
+            alphabet: alphabet.clone(),
+            ..self
+        }
+    }
+
+    /// This list followed by any words from `other` that aren't already on it.
+    pub fn union(&self, other: &[WordN<N>]) -> WordList<N> {
+        let list: WordList<N> = self.words.iter().chain(other).copied().collect();
+        list.in_alphabet(&self.alphabet)
+    }
+
+    /// The alphabet words on this list are spelled in.
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    pub fn words(&self) -> &[WordN<N>] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<WordN<N>> {
+        self.words.get(index).copied()
+    }
+
+    pub fn index_of(&self, word: &WordN<N>) -> Option<usize> {
+        self.index.get(word).copied()
+    }
+
+    pub fn contains(&self, word: &WordN<N>) -> bool {
+        self.index.contains_key(word)
+    }
+
+    /// The answer to puzzle number `puzzle`, treating this as a list of targets.
+    pub fn puzzle(&self, puzzle: usize) -> Result<WordN<N>, WordError> {
+        self.get(puzzle).ok_or(WordError::Puzzle(puzzle))
+    }
+
+    /// Parses a word that has to be on this list, in any mix of case.
+    pub fn word(&self, value: &str) -> Result<WordN<N>, WordError> {
+        let word = self.alphabet.word(value)?;
+        if !self.contains(&word) {
+            return Err(WordError::NotWord(value.into()));
+        }
+        Ok(word)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::alphabet::Alphabet;
+    use crate::word_list::{WordList, WordListError};
+    use crate::words::TARGET_WORDS;
+    use crate::{Word, WordError};
+    use anyhow::Error;
+
+    #[test]
+    fn lists_are_parsed_from_text_and_json() -> Result<(), Error> {
+        let text = WordList::parse("cigar rebut\nsissy\n\nqajaq\n")?;
+        let json = WordList::parse(r#"["cigar", "rebut", "sissy", "qajaq"]"#)?;
+        assert_eq!(text, json);
+        assert_eq!(text.len(), 4);
+        assert_eq!(text.index_of(&Word::try_from("sissy")?), Some(2));
+        assert_eq!(text.puzzle(1)?, Word::try_from("rebut")?);
+        assert!(matches!(text.puzzle(4), Err(WordError::Puzzle(4))));
+        Ok(())
+    }
+
+    #[test]
+    fn listed_words_need_not_be_built_in() -> Result<(), Error> {
+        let list: WordList = WordList::parse("cigar xyzzy")?;
+        assert!(Word::try_from("xyzzy").is_err());
+        assert!(list.word("xyzzy").is_ok());
+        assert_eq!(list.word("XyZzY")?, list.word("xyzzy")?);
+        assert!(matches!(list.word("rebut"), Err(WordError::NotWord(_))));
+        assert!(matches!(
+            WordList::<5>::parse("cigar reb-t"),
+            Err(WordListError::Word(2, WordError::Chars(_, '-')))
+        ));
+        assert!(matches!(
+            WordList::<5>::parse(r#"["cigar", "rebuts"]"#),
+            Err(WordListError::Word(2, WordError::Length(6, 5)))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn lists_can_have_longer_words() -> Result<(), Error> {
+        let list = WordList::<6>::parse("rebuts cigars")?;
+        assert_eq!(list.word("cigars")?.to_string(), "cigars");
+        assert!(matches!(list.word("cigar"), Err(WordError::Length(5, 6))));
+        Ok(())
+    }
+
+    #[test]
+    fn lists_keep_their_alphabet() -> Result<(), Error> {
+        let list: WordList = WordList::parse_in("señal añejo", &Alphabet::spanish())?;
+        assert_eq!(list.word("SEÑAL")?.to_string(), "señal");
+        assert_eq!(list.union(&[]).alphabet(), &Alphabet::spanish());
+        assert!(WordList::<5>::parse("señal").is_err());
+
+        let targets = WordList::targets().spelled_in(&Alphabet::spanish())?;
+        assert_eq!(targets.word("Cigar")?.to_string(), "cigar");
+        let guesses = targets.union(list.words());
+        assert_eq!(guesses.word("añejo")?.to_string(), "añejo");
+        let chair: WordList = WordList::parse("cigar chair")?;
+        let ch: Alphabet = "a,b,c,d,e,f,g,h,i,j,k,l,m,n,o,p,q,r,s,t,u,v,w,x,y,z,ch".parse()?;
+        assert!(matches!(
+            chair.spelled_in(&ch),
+            Err(WordListError::Word(2, WordError::Length(4, 5)))
+        ));
+        assert!(matches!(
+            WordList::targets().spelled_in(&Alphabet::welsh()),
+            Err(WordListError::Word(
+                _,
+                WordError::Chars(_, 'k' | 'q' | 'v' | 'x' | 'z')
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn embedded_list_has_targets_first() {
+        let embedded = WordList::embedded();
+        assert_eq!(&embedded.words()[..TARGET_WORDS.len()], &TARGET_WORDS[..]);
+        assert_eq!(
+            WordList::targets().union(embedded.words()).words(),
+            embedded.words()
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use crate::knowledge::Knowledge;
+use crate::word_list::WordList;
+use crate::words::TARGET_WORDS;
+use crate::{GuessStatusN, WordError, WordGuessN, WordN};
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// A set of words, stored as one bit for each word on a [`WordList`].
+///
+/// Unless another list is given, that's every built-in word: the targets followed by the
+/// extended list.  Sets take a couple of kilobytes whatever their size, and combining two sets
+/// over the same list is a handful of word-sized operations.  Iteration is in list order.
+#[derive(Clone)]
+pub struct WordSet<const N: usize = 5> {
+    list: Arc<WordList<N>>,
+    bits: Vec<u64>,
+}
+
+impl Default for WordSet {
+    fn default() -> Self {
+        WordSet::empty(&WordList::embedded())
+    }
+}
+
+impl<const N: usize> PartialEq for WordSet<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits && self.same_list(other)
+    }
+}
+
+impl<const N: usize> Eq for WordSet<N> {}
+
+/// Only the non-empty blocks are hashed, as most sets are much smaller than the word lists.
+impl<const N: usize> Hash for WordSet<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits
+            .iter()
+            .enumerate()
+            .filter(|(_, &block)| block != 0)
+            .for_each(|(i, block)| {
+                state.write_usize(i);
+                state.write_u64(*block);
+            });
+    }
+}
+
+impl<const N: usize> Debug for WordSet<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl WordSet {
+    pub fn new() -> WordSet {
+        WordSet::default()
+    }
+
+    /// Every built-in word, targets and extended list.
+    pub fn all() -> WordSet {
+        WordSet::full(&WordList::embedded())
+    }
+
+    /// Every built-in word that can be a target.
+    pub fn targets() -> WordSet {
+        WordSet::first(&WordList::embedded(), TARGET_WORDS.len())
+    }
+}
+
+impl<const N: usize> WordSet<N> {
+    /// No words, out of those on `list`.
+    pub fn empty(list: &Arc<WordList<N>>) -> WordSet<N> {
+        WordSet {
+            list: list.clone(),
+            bits: vec![0; list.len().div_ceil(64)],
+        }
+    }
+
+    /// Every word on `list`.
+    pub fn full(list: &Arc<WordList<N>>) -> WordSet<N> {
+        WordSet::first(list, list.len())
+    }
+
+    fn first(list: &Arc<WordList<N>>, n: usize) -> WordSet<N> {
+        let mut set = WordSet::empty(list);
+        for (i, block) in set.bits.iter_mut().enumerate() {
+            let start = i * 64;
+            if n >= start + 64 {
+                *block = u64::MAX;
+            } else if n > start {
+                *block = (1 << (n - start)) - 1;
+            }
+        }
+        set
+    }
+
+    /// The list this is a set of words from.
+    pub fn list(&self) -> &Arc<WordList<N>> {
+        &self.list
+    }
+
+    /// The words in `words` that would give `guess` the `status`.
+    pub fn matching(words: &WordSet<N>, guess: WordN<N>, status: GuessStatusN<N>) -> WordSet<N> {
+        words.filter(|target| WordGuessN::guess_from(guess, target).status == status)
+    }
+
+    /// The words in `words` consistent with `knowledge`.
+    pub fn consistent(words: &WordSet<N>, knowledge: &Knowledge<N>) -> WordSet<N> {
+        words.filter(|target| knowledge.matches(target))
+    }
+
+    /// The `words` out of those on `list`, or an error for the first that isn't on it.
+    pub fn from_words<I>(list: &Arc<WordList<N>>, words: I) -> Result<WordSet<N>, WordError>
+    where
+        I: IntoIterator<Item = WordN<N>>,
+    {
+        let mut set = WordSet::empty(list);
+        for word in words {
+            set.insert(word)?;
+        }
+        Ok(set)
+    }
+
+    /// Adds `word`, returning whether it wasn't already in the set, or an error if it isn't on
+    /// the list.
+    pub fn insert(&mut self, word: WordN<N>) -> Result<bool, WordError> {
+        let i = self
+            .list
+            .index_of(&word)
+            .ok_or_else(|| WordError::NotWord(word.to_string()))?;
+        let mask = 1 << (i % 64);
+        let added = self.bits[i / 64] & mask == 0;
+        self.bits[i / 64] |= mask;
+        Ok(added)
+    }
+
+    pub fn remove(&mut self, word: &WordN<N>) -> bool {
+        match self.list.index_of(word) {
+            Some(i) => {
+                let mask = 1 << (i % 64);
+                let removed = self.bits[i / 64] & mask != 0;
+                self.bits[i / 64] &= !mask;
+                removed
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, word: &WordN<N>) -> bool {
+        self.list
+            .index_of(word)
+            .map(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+            .unwrap_or(false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&b| b == 0)
+    }
+
+    /// The words in this set that satisfy `f`.
+    pub fn filter<F: Fn(&WordN<N>) -> bool>(&self, f: F) -> WordSet<N> {
+        let mut set = WordSet::empty(&self.list);
+        for (i, word) in self.indexed() {
+            if f(&word) {
+                set.bits[i / 64] |= 1 << (i % 64);
+            }
+        }
+        set
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = WordN<N>> + '_ {
+        self.indexed().map(|(_, word)| word)
+    }
+
+    fn indexed(&self) -> impl Iterator<Item = (usize, WordN<N>)> + '_ {
+        self.bits.iter().enumerate().flat_map(move |(i, &block)| {
+            let mut block = block;
+            std::iter::from_fn(move || {
+                if block == 0 {
+                    return None;
+                }
+                let index = i * 64 + block.trailing_zeros() as usize;
+                block &= block - 1;
+                self.list.get(index).map(|word| (index, word))
+            })
+        })
+    }
+
+    /// The words in both sets, which have to be over the same list.
+    pub fn intersection(&self, other: &WordSet<N>) -> Result<WordSet<N>, WordError> {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// The words in either set, which have to be over the same list.
+    pub fn union(&self, other: &WordSet<N>) -> Result<WordSet<N>, WordError> {
+        self.combine(other, |a, b| a | b)
+    }
+
+    fn same_list(&self, other: &WordSet<N>) -> bool {
+        Arc::ptr_eq(&self.list, &other.list) || self.list == other.list
+    }
+
+    fn combine(&self, other: &WordSet<N>, f: fn(u64, u64) -> u64) -> Result<WordSet<N>, WordError> {
+        if !self.same_list(other) {
+            return Err(WordError::Lists);
+        }
+        Ok(WordSet {
+            list: self.list.clone(),
+            bits: self
+                .bits
+                .iter()
+                .zip(other.bits.iter())
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::knowledge::Knowledge;
+    use crate::solver::candidates;
+    use crate::word_list::WordList;
+    use crate::word_set::WordSet;
+    use crate::words::{EXTENDED_WORDS, TARGET_WORDS, WORD_COUNT};
+    use crate::{GuessStatus, Word, WordError, WordGuess};
+    use anyhow::Error;
+    use std::sync::Arc;
+
+    #[test]
+    fn sets_count_their_words() {
+        assert_eq!(WordSet::all().len(), WORD_COUNT);
+        assert_eq!(WordSet::targets().len(), TARGET_WORDS.len());
+        assert!(WordSet::new().is_empty());
+        assert!(WordSet::targets().iter().eq(TARGET_WORDS.iter().copied()));
+        assert_eq!(WordSet::all().iter().last(), EXTENDED_WORDS.last().copied());
+    }
+
+    #[test]
+    fn sets_insert_and_remove() -> Result<(), Error> {
+        let mut set = WordSet::new();
+        let word = Word::try_from("zymic")?;
+        assert!(set.insert(word)?);
+        assert!(!set.insert(word)?);
+        assert!(set.contains(&word));
+        assert_eq!(set.len(), 1);
+        assert!(set.remove(&word));
+        assert!(!set.remove(&word));
+        assert!(set.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn sets_combine() -> Result<(), Error> {
+        let embedded = WordList::embedded();
+        let a = WordSet::from_words(&embedded, TARGET_WORDS[..20].iter().copied())?;
+        let b = WordSet::from_words(&embedded, TARGET_WORDS[10..30].iter().copied())?;
+        assert!(a
+            .intersection(&b)?
+            .iter()
+            .eq(TARGET_WORDS[10..20].iter().copied()));
+        assert!(a.union(&b)?.iter().eq(TARGET_WORDS[..30].iter().copied()));
+        Ok(())
+    }
+
+    #[test]
+    fn sets_keep_to_their_list() -> Result<(), Error> {
+        let list = Arc::new(WordList::parse("cigar xyzzy rebut")?);
+        let xyzzy = Word::from_letters("xyzzy")?;
+        assert!(matches!(
+            WordSet::new().insert(xyzzy),
+            Err(WordError::NotWord(_))
+        ));
+        assert!(WordSet::from_words(&list, [xyzzy]).is_ok());
+        let other = WordSet::full(&list);
+        assert!(matches!(
+            WordSet::targets().intersection(&other),
+            Err(WordError::Lists)
+        ));
+        assert!(matches!(
+            WordSet::targets().union(&other),
+            Err(WordError::Lists)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn sets_filter_like_candidates() -> Result<(), Error> {
+        let history = [WordGuess::new(
+            Word::try_from("raise")?,
+            GuessStatus::try_from("-+--=")?,
+        )];
+        let expected = candidates(TARGET_WORDS.iter(), &history);
+        let knowledge = Knowledge::from_history(&history);
+        let consistent = WordSet::consistent(&WordSet::targets(), &knowledge);
+        assert!(consistent.iter().eq(expected.iter().copied()));
+        let matching = WordSet::matching(&WordSet::targets(), history[0].word(), history[0].status);
+        assert_eq!(matching, consistent);
+        Ok(())
+    }
+
+    #[test]
+    fn sets_use_their_list() -> Result<(), Error> {
+        let list = Arc::new(WordList::parse("cigar xyzzy rebut")?);
+        let xyzzy = Word::from_letters("xyzzy")?;
+        let set = WordSet::full(&list);
+        assert!(set.contains(&xyzzy));
+        assert!(!set.contains(&Word::try_from("sissy")?));
+        let without = set.filter(|&w| w != xyzzy);
+        assert!(without
+            .iter()
+            .eq(list.words().iter().copied().filter(|&w| w != xyzzy)));
+        assert_ne!(without, WordSet::targets().filter(|w| without.contains(w)));
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use crate::Word;
+use crate::{Tile, Word, WordError, WordN};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 macro_rules! words {
     ($($x:literal),+ $(,)?) => (
@@ -6,17 +8,19 @@ macro_rules! words {
     );
 }
 
-const fn word(s: &'static str) -> Word {
-    let mut a: [u8; 5] = [0; 5];
+const fn word<const N: usize>(s: &'static str) -> WordN<N> {
+    let mut a = [Tile::ascii(b'a'); N];
     let s = s.as_bytes();
-    a[0] = s[0];
-    a[1] = s[1];
-    a[2] = s[2];
-    a[3] = s[3];
-    a[4] = s[4];
-    Word(a)
+    assert!(s.len() == N, "words in a list all have the same length");
+    let mut i = 0;
+    while i < N {
+        a[i] = Tile::ascii(s[i]);
+        i += 1;
+    }
+    WordN(a)
 }
 
+#[allow(clippy::large_const_arrays)]
 pub const TARGET_WORDS: [Word; 2315] = words![
     "cigar", "rebut", "sissy", "humph", "awake", "blush", "focal", "evade", "naval", "serve",
     "heath", "dwarf", "model", "karma", "stink", "grade", "quiet", "bench", "abate", "feign",
@@ -252,6 +256,7 @@ pub const TARGET_WORDS: [Word; 2315] = words![
     "judge", "rower", "artsy", "rural", "shave"
 ];
 
+#[allow(clippy::large_const_arrays)]
 pub const EXTENDED_WORDS: [Word; 10657] = words![
     "aahed", "aalii", "aargh", "aarti", "abaca", "abaci", "abacs", "abaft", "abaka", "abamp",
     "aband", "abash", "abask", "abaya", "abbas", "abbed", "abbes", "abcee", "abeam", "abear",
@@ -1320,3 +1325,266 @@ pub const EXTENDED_WORDS: [Word; 10657] = words![
     "zoppo", "zoril", "zoris", "zorro", "zouks", "zowee", "zowie", "zulus", "zupan", "zupas",
     "zuppa", "zurfs", "zuzim", "zygal", "zygon", "zymes", "zymic"
 ];
+
+/// Compares built-in words, whose tiles are all single ASCII letters.
+const fn less(a: &Word, b: &Word) -> bool {
+    let mut i = 0;
+    while i < 5 {
+        if a.0[i].0[0] != b.0[i].0[0] {
+            return a.0[i].0[0] < b.0[i].0[0];
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn is_sorted(words: &[Word]) -> bool {
+    let mut i = 1;
+    while i < words.len() {
+        if !less(&words[i - 1], &words[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// Membership of the extended list is checked with a binary search, so it must stay sorted.
+const _: () = assert!(is_sorted(&EXTENDED_WORDS));
+
+/// Heap sorts the targets along with their puzzle numbers, at compile time.
+const fn sorted_targets() -> [(Word, u16); TARGET_WORDS.len()] {
+    let mut index = [(word("aaaaa"), 0); TARGET_WORDS.len()];
+    let mut i = 0;
+    while i < index.len() {
+        index[i] = (TARGET_WORDS[i], i as u16);
+        i += 1;
+    }
+    let mut end = index.len();
+    let mut start = end / 2;
+    while end > 1 {
+        if start > 0 {
+            start -= 1;
+        } else {
+            end -= 1;
+            let top = index[0];
+            index[0] = index[end];
+            index[end] = top;
+        }
+        let mut root = start;
+        while 2 * root + 1 < end {
+            let mut child = 2 * root + 1;
+            if child + 1 < end && less(&index[child].0, &index[child + 1].0) {
+                child += 1;
+            }
+            if !less(&index[root].0, &index[child].0) {
+                break;
+            }
+            let parent = index[root];
+            index[root] = index[child];
+            index[child] = parent;
+            root = child;
+        }
+    }
+    index
+}
+
+static SORTED_TARGETS: [(Word, u16); TARGET_WORDS.len()] = sorted_targets();
+
+/// Targets the New York Times took off the list when it took the game over.
+const REMOVED_BY_NYT: [Word; 6] = words!["agora", "pupal", "lynch", "fibre", "slave", "wench"];
+
+/// The words in `words` that aren't in `removed`, in the same order.  `N` has to be exactly how
+/// many are left.
+const fn without<const N: usize>(words: &[Word], removed: &[Word]) -> [Word; N] {
+    let mut kept = [word("aaaaa"); N];
+    let mut n = 0;
+    let mut i = 0;
+    while i < words.len() {
+        let mut j = 0;
+        while j < removed.len() && (less(&words[i], &removed[j]) || less(&removed[j], &words[i])) {
+            j += 1;
+        }
+        if j == removed.len() {
+            kept[n] = words[i];
+            n += 1;
+        }
+        i += 1;
+    }
+    assert!(n == N, "the wrong number of words were removed");
+    kept
+}
+
+/// The targets as the New York Times curated them: the original list without a few words.
+#[allow(clippy::large_const_arrays)]
+pub const NYT_TARGET_WORDS: [Word; 2309] = without(&TARGET_WORDS, &REMOVED_BY_NYT);
+
+/// Which of the built-in lists of targets was in use.
+///
+/// The default is the original list, which is what [`TARGET_WORDS`] has always been.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ListVersion {
+    /// The list the game started with, [`TARGET_WORDS`].
+    #[default]
+    Original,
+    /// The list from when the New York Times took over, [`NYT_TARGET_WORDS`].
+    Nyt,
+}
+
+impl ListVersion {
+    /// The first puzzle set from the New York Times site.
+    const NYT_FIRST_PUZZLE: usize = 236;
+
+    /// The targets, in puzzle order.
+    pub fn targets(self) -> &'static [Word] {
+        match self {
+            ListVersion::Original => &TARGET_WORDS,
+            ListVersion::Nyt => &NYT_TARGET_WORDS,
+        }
+    }
+
+    /// The list that was in use for puzzle number `puzzle`.
+    pub fn for_puzzle(puzzle: usize) -> ListVersion {
+        if puzzle < ListVersion::NYT_FIRST_PUZZLE {
+            ListVersion::Original
+        } else {
+            ListVersion::Nyt
+        }
+    }
+}
+
+impl Display for ListVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ListVersion::Original => "original",
+            ListVersion::Nyt => "nyt",
+        })
+    }
+}
+
+impl FromStr for ListVersion {
+    type Err = WordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "original" => Ok(ListVersion::Original),
+            "nyt" => Ok(ListVersion::Nyt),
+            _ => Err(WordError::Version(s.to_string())),
+        }
+    }
+}
+
+/// The puzzle number that `word` is the answer to on the original list, if it's a target.
+pub fn target_index(word: &Word) -> Option<usize> {
+    SORTED_TARGETS
+        .binary_search_by(|(w, _)| w.cmp(word))
+        .ok()
+        .map(|i| SORTED_TARGETS[i].1 as usize)
+}
+
+/// The answer to puzzle number `puzzle` on the list in use at the time, or an error if the list
+/// doesn't go that far.
+pub fn puzzle_target(puzzle: usize) -> Result<Word, WordError> {
+    ListVersion::for_puzzle(puzzle)
+        .targets()
+        .get(puzzle)
+        .copied()
+        .ok_or(WordError::Puzzle(puzzle))
+}
+
+/// Whether `word` is on the list of words that can be answers.
+pub fn is_target(word: &Word) -> bool {
+    target_index(word).is_some()
+}
+
+/// Whether `word` is accepted as a guess: either a target, or on the extended list.
+pub fn is_valid_guess(word: &Word) -> bool {
+    index_of(word).is_some()
+}
+
+/// How many words there are: the targets, then the extended list.
+pub const WORD_COUNT: usize = TARGET_WORDS.len() + EXTENDED_WORDS.len();
+
+/// Where `word` is in the targets followed by the extended list.
+pub fn index_of(word: &Word) -> Option<usize> {
+    target_index(word).or_else(|| {
+        EXTENDED_WORDS
+            .binary_search(word)
+            .ok()
+            .map(|i| i + TARGET_WORDS.len())
+    })
+}
+
+/// The word at `index` in the targets followed by the extended list.
+pub fn word_at(index: usize) -> Option<Word> {
+    if index < TARGET_WORDS.len() {
+        Some(TARGET_WORDS[index])
+    } else {
+        EXTENDED_WORDS.get(index - TARGET_WORDS.len()).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::words::{
+        index_of, is_target, is_valid_guess, puzzle_target, target_index, word_at, ListVersion,
+        EXTENDED_WORDS, NYT_TARGET_WORDS, TARGET_WORDS, WORD_COUNT,
+    };
+    use crate::{Word, WordError};
+    use anyhow::Error;
+
+    #[test]
+    fn targets_are_indexed_by_puzzle() {
+        for (i, word) in TARGET_WORDS.iter().enumerate() {
+            assert_eq!(target_index(word), Some(i));
+        }
+    }
+
+    #[test]
+    fn every_word_is_a_valid_guess() {
+        assert!(TARGET_WORDS.iter().all(is_valid_guess));
+        assert!(EXTENDED_WORDS.iter().all(is_valid_guess));
+        assert!(!EXTENDED_WORDS.iter().any(is_target));
+    }
+
+    #[test]
+    fn words_are_indexed_in_order() {
+        let words = TARGET_WORDS.iter().chain(EXTENDED_WORDS.iter());
+        for (i, word) in words.enumerate() {
+            assert_eq!(index_of(word), Some(i));
+            assert_eq!(word_at(i), Some(*word));
+        }
+        assert_eq!(word_at(WORD_COUNT), None);
+    }
+
+    #[test]
+    fn puzzles_past_the_list_are_errors() -> Result<(), Error> {
+        assert_eq!(puzzle_target(0)?, Word::try_from("cigar")?);
+        assert_eq!(puzzle_target(2308)?, TARGET_WORDS[2314]);
+        assert!(matches!(puzzle_target(2309), Err(WordError::Puzzle(2309))));
+        Ok(())
+    }
+
+    #[test]
+    fn puzzles_use_the_list_of_the_time() -> Result<(), Error> {
+        assert_eq!(ListVersion::for_puzzle(235), ListVersion::Original);
+        assert_eq!(ListVersion::for_puzzle(236), ListVersion::Nyt);
+        assert_eq!(NYT_TARGET_WORDS[..241], TARGET_WORDS[..241]);
+        assert_eq!(TARGET_WORDS[241], Word::try_from("agora")?);
+        assert_eq!(puzzle_target(241)?, Word::try_from("aroma")?);
+        assert!(NYT_TARGET_WORDS.iter().all(is_target));
+        assert_eq!("original".parse::<ListVersion>()?, ListVersion::Original);
+        assert_eq!(ListVersion::default(), ListVersion::Original);
+        assert!("nytimes".parse::<ListVersion>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn non_words_are_rejected() {
+        assert!(Word::try_from("aaaaa").is_err());
+        assert!(Word::try_from("zzzzz").is_err());
+        assert!(Word::try_from("cigar").is_ok());
+        assert!(Word::try_from("aahed").is_ok());
+        assert!(Word::try_from("zymic").is_ok());
+    }
+}